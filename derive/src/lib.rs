@@ -0,0 +1,84 @@
+//! `#[derive(DebugUnwrap)]` for [bugsalot](https://docs.rs/bugsalot/).  Use via bugsalot's `derive` feature.
+
+extern crate proc_macro;
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{parse_macro_input, parse_quote, Data, DeriveInput, Error, Variant};
+
+/// Implements `bugsalot::DebugUnwrap` for an enum, so [unwrap!] and [expect!] can be used on it.
+///
+/// Mark passing variants with `#[ok]`, failing variants with `#[err]`, or both.  Unmarked variants are whatever the
+/// marked ones aren't.  Unwrapping returns the enum itself, and the enum must implement `Debug`.
+///
+/// [unwrap!]:  https://docs.rs/bugsalot/*/bugsalot/macro.unwrap.html
+/// [expect!]:  https://docs.rs/bugsalot/*/bugsalot/macro.expect.html
+#[proc_macro_derive(DebugUnwrap, attributes(ok, err))]
+pub fn derive_debug_unwrap(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match expand(&input) {
+        Ok(tokens)  => tokens.into(),
+        Err(err)    => err.to_compile_error().into(),
+    }
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let data = match &input.data {
+        Data::Enum(data) => data,
+        _ => return Err(Error::new_spanned(&input.ident, "#[derive(DebugUnwrap)] only supports enums")),
+    };
+
+    let mut marked_ok   = Vec::new();
+    let mut marked_err  = Vec::new();
+    let mut unmarked    = Vec::new();
+    for variant in data.variants.iter() {
+        match (has_attr(variant, "ok"), has_attr(variant, "err")) {
+            (true,  true ) => return Err(Error::new_spanned(variant, "variant can't be both #[ok] and #[err]")),
+            (true,  false) => marked_ok.push(variant),
+            (false, true ) => marked_err.push(variant),
+            (false, false) => unmarked.push(variant),
+        }
+    }
+
+    let ok = match (marked_ok.is_empty(), marked_err.is_empty()) {
+        (true,  true ) => return Err(Error::new_spanned(&input.ident, "mark at least one variant with #[ok] or #[err]")),
+        (true,  false) => unmarked,
+        (false, true ) => marked_ok,
+        (false, false) => match unmarked.first() {
+            Some(variant)   => return Err(Error::new_spanned(variant, "variant must be marked #[ok] or #[err]")),
+            None            => marked_ok,
+        },
+    };
+    if ok.is_empty() { return Err(Error::new_spanned(&input.ident, "at least one variant must be #[ok]")); }
+
+    let pass        = ok.iter().map(|v| v.ident.to_string()).collect::<Vec<_>>().join(" | ");
+    let pass_parens = ok.len() == 1 && !ok[0].fields.is_empty();
+    let fail_arms   = data.variants.iter().map(|v| {
+        let ident = &v.ident;
+        // Variants with fields are shown via the enum's own `Debug`, which already names the variant.
+        let (fail, fail_parens) = if v.fields.is_empty() { (ident.to_string(), false) } else { (String::new(), true) };
+        quote! { Self::#ident { .. } => (#pass, #fail, #pass_parens, #fail_parens), }
+    });
+    let ok_idents   = ok.iter().map(|v| &v.ident);
+
+    let name = &input.ident;
+    let mut generics = input.generics.clone();
+    generics.make_where_clause().predicates.push(parse_quote!(Self: ::core::fmt::Debug));
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::bugsalot::DebugUnwrap<Self, Self> for #name #ty_generics #where_clause {
+            fn get_pass_fail_strs(&self) -> (&'static str, &'static str, bool, bool) {
+                match self { #(#fail_arms)* }
+            }
+            fn can_unwrap(&self) -> bool { matches!(self, #(Self::#ok_idents { .. })|*) }
+            fn unwrap_ok(self) -> Self { self }
+            fn unwrap_err(self) -> Self { self }
+        }
+    })
+}
+
+fn has_attr(variant: &Variant, name: &str) -> bool {
+    variant.attrs.iter().any(|attr| attr.path().is_ident(name))
+}
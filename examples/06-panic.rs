@@ -0,0 +1,6 @@
+fn main() {
+    bugsalot::install_panic_hook();
+
+    let v : Vec<i32> = Vec::new();
+    let _ = v[0]; // Debugger will break here (via the panic hook) before unwinding
+}
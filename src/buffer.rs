@@ -0,0 +1,121 @@
+use alloc::vec::Vec;
+use core::fmt::{self, Write};
+
+/// Messages up to this many bytes (including any `\0`) are formatted without touching the heap.
+const STACK_CAPACITY : usize = 512;
+
+/// Appended to messages that couldn't be fully formatted, because the heap refused to grow.
+const TRUNCATED : &str = "...\r\n";
+
+/// A formatting buffer for output that avoids heap allocation for short messages, and survives heap allocation
+/// failures for long ones (by truncating.)
+pub(crate) struct Buffer {
+    stack:      [u8; STACK_CAPACITY],
+    stack_len:  usize,
+    heap:       Vec<u8>,
+    truncated:  bool,
+}
+
+impl Buffer {
+    pub fn new() -> Self {
+        Self { stack: [0; STACK_CAPACITY], stack_len: 0, heap: Vec::new(), truncated: false }
+    }
+
+    /// Format `args` into a new buffer.
+    pub fn format(args: fmt::Arguments) -> Self {
+        let mut buffer = Self::new();
+        let _ = buffer.write_fmt(args);
+        buffer
+    }
+
+    /// Ensure the buffer ends with `\0`, for C APIs like `OutputDebugStringA`.
+    #[allow(dead_code)] // Only used on some platforms
+    pub fn require_nul(&mut self) {
+        if self.as_bytes().last() != Some(&0) { let _ = self.write_str("\0"); }
+        if self.truncated {
+            // Make room for the terminator by sacrificing the last byte of the truncation marker
+            let bytes = self.as_bytes_mut();
+            let n = bytes.len();
+            bytes[n-1] = 0;
+        }
+    }
+
+    /// `true` if the heap refused to grow, and the buffer's contents end with a truncation marker instead.
+    pub fn is_truncated(&self) -> bool { self.truncated }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        if self.heap.is_empty() { &self.stack[..self.stack_len] } else { &self.heap[..] }
+    }
+
+    /// The buffer's contents, minus any `\0` terminator.
+    pub fn as_str(&self) -> &str {
+        let bytes = self.as_bytes();
+        let bytes = if bytes.last() == Some(&0) { &bytes[..bytes.len()-1] } else { bytes };
+        // Only whole `&str`s - or `&str`s cut at char boundaries - are ever written.
+        core::str::from_utf8(bytes).unwrap_or("")
+    }
+
+    fn as_bytes_mut(&mut self) -> &mut [u8] {
+        if self.heap.is_empty() { &mut self.stack[..self.stack_len] } else { &mut self.heap[..] }
+    }
+
+    fn truncate(&mut self) {
+        self.truncated = true;
+        let bytes = self.as_bytes_mut();
+        let n = bytes.len();
+        if n >= TRUNCATED.len() {
+            let mut start = n - TRUNCATED.len();
+            while start > 0 && (bytes[start] & 0xC0) == 0x80 { start -= 1; } // Don't split a UTF-8 sequence
+            for b in bytes[start..].iter_mut() { *b = b'.'; }
+            bytes[n-TRUNCATED.len()..].copy_from_slice(TRUNCATED.as_bytes());
+        }
+    }
+}
+
+impl Write for Buffer {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        if self.truncated { return Ok(()); }
+
+        if self.heap.is_empty() {
+            if let Some(dst) = self.stack.get_mut(self.stack_len .. self.stack_len + s.len()) {
+                dst.copy_from_slice(s.as_bytes());
+                self.stack_len += s.len();
+                return Ok(());
+            }
+            // Spill to the heap
+            if self.heap.try_reserve(2 * (self.stack_len + s.len())).is_err() {
+                let mut fits = STACK_CAPACITY - self.stack_len;
+                while !s.is_char_boundary(fits) { fits -= 1; }
+                self.stack[self.stack_len .. self.stack_len + fits].copy_from_slice(&s.as_bytes()[..fits]);
+                self.stack_len += fits;
+                self.truncate();
+                return Ok(());
+            }
+            self.heap.extend_from_slice(&self.stack[..self.stack_len]);
+        }
+
+        if self.heap.try_reserve(s.len()).is_err() {
+            let mut fits = self.heap.capacity() - self.heap.len();
+            while !s.is_char_boundary(fits) { fits -= 1; }
+            self.heap.extend_from_slice(&s.as_bytes()[..fits]);
+            self.truncate();
+            return Ok(());
+        }
+        self.heap.extend_from_slice(s.as_bytes());
+        Ok(())
+    }
+}
+
+#[test]
+fn buffer_stack_and_heap() {
+    let short = Buffer::format(format_args!("{}({}): {}\r\n", file!(), 42, "short"));
+    assert!(short.heap.is_empty());
+    assert_eq!(short.as_str(), concat!(file!(), "(42): short\r\n"));
+
+    let long = "x".repeat(3 * STACK_CAPACITY);
+    let mut long_buffer = Buffer::format(format_args!("{}", long));
+    assert!(!long_buffer.heap.is_empty());
+    long_buffer.require_nul();
+    assert_eq!(long_buffer.as_bytes().last(), Some(&0));
+    assert_eq!(long_buffer.as_str(), long);
+}
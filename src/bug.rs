@@ -0,0 +1,71 @@
+use core::error::Error;
+use core::fmt::{self, Display, Formatter};
+use core::panic::Location;
+
+/// An error that has already been reported as a bug, wrapping the original error `E`.
+///
+/// Returned by [ResultBugExt::bug_err], so a function can report a bug at the site of a failure, and still propagate
+/// the failure to its caller with `?`.  It displays as the original error, prefixed with where it was reported - e.g.
+/// `bug reported at src/main.rs:12:5: invalid digit found in string` - and its `source()` is that of the original
+/// error.  The original error itself is available as [error](#method.error).
+///
+/// # Examples
+///
+/// ```no_run
+/// use bugsalot::{Bug, ResultBugExt};
+///
+/// fn parse(s: &str) -> Result<i32, Bug<std::num::ParseIntError>> {
+///     let i = s.parse::<i32>().bug_err()?; // Reports, then returns Err(Bug { ... })
+///     Ok(i)
+/// }
+/// ```
+///
+/// [ResultBugExt::bug_err]:    trait.ResultBugExt.html#tymethod.bug_err
+#[derive(Clone, Debug)]
+pub struct Bug<E> {
+    error:      E,
+    location:   &'static Location<'static>,
+}
+
+impl<E> Bug<E> {
+    /// Wrap `error`, which was reported as a bug at `location`.
+    pub fn new(error: E, location: &'static Location<'static>) -> Self { Self { error, location } }
+
+    /// The original error.
+    pub fn error(&self) -> &E { &self.error }
+
+    /// Where the bug was reported.
+    pub fn location(&self) -> &'static Location<'static> { self.location }
+
+    /// Unwrap the original error.
+    pub fn into_inner(self) -> E { self.error }
+}
+
+impl<E: Display> Display for Bug<E> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "bug reported at {}: {}", crate::macro_impl::Site::from_location(self.location, ""), self.error)
+    }
+}
+
+impl<E: Error> Error for Bug<E> {
+    fn source(&self) -> Option<&(dyn Error + 'static)> { self.error.source() } // self.error is already in our Display
+}
+
+#[test]
+fn bug_error() {
+    use alloc::string::ToString;
+
+    #[derive(Debug)] struct ConfigError(core::num::ParseIntError);
+    impl Display for ConfigError { fn fmt(&self, f: &mut Formatter) -> fmt::Result { f.write_str("bad config") } }
+    impl Error for ConfigError { fn source(&self) -> Option<&(dyn Error + 'static)> { Some(&self.0) } }
+
+    let location = Location::caller();
+    let bug = Bug::new(ConfigError("x".parse::<i32>().unwrap_err()), location);
+    let (line, column) = (location.line(), location.column());
+    let expected = if cfg!(windows) { alloc::format!("{}({},{})", file!(), line, column) } else { alloc::format!("{}:{}:{}", file!(), line, column) };
+    assert_eq!(bug.to_string(), alloc::format!("bug reported at {}: bad config", expected)); // In the platform's default LocationStyle
+    assert_eq!(bug.source().map(|e| e.to_string()), Some("invalid digit found in string".to_string()));
+    assert_eq!(bug.into_inner().0, "x".parse::<i32>().unwrap_err());
+
+    assert_eq!(Bug::new("reason", location).to_string(), alloc::format!("bug reported at {}: reason", expected));
+}
@@ -82,31 +82,44 @@ pub fn state () -> State {
     }
 
     // "/proc/self/status" may contain a TracerPid: [debugger process id] line, which is nonzero if there is a debugger.
-    // Works on android, linux, and possibly on various BSDs and OS X.
+    // Works on android, linux, and possibly on various BSDs and OS X.  Read without `std::fs` or heap allocation, so
+    // reports still work without `std`, or with a failing allocator.
     #[cfg(unix)] {
         // The following `/proc/version`s of WSL correctly report `TracerPid`:
         // Linux version 4.4.0-18362-Microsoft (Microsoft@Microsoft.com) (gcc version 5.4.0 (GCC) ) #1-Microsoft Mon Mar 18 12:02:00 PST 2019
         // XXX: Do we maybe want to cache the result in a thread_local and/or static somewhere?
-        if let Ok(file) = std::fs::File::open("/proc/self/status") {
-            let file = std::io::BufReader::new(file);
-            use std::io::BufRead;
-            for line in file.lines() {
-                if let Ok(line) = line {
-                    let line = line.trim();
-                    if line.starts_with("TracerPid:") {
-                        return match [" 0", "\t0", ":0"].iter().any(|zero| line.ends_with(zero)) {
-                            true  => State::Detatched,
-                            false => State::Attached,
-                        }
-                    }
+        use crate::ffi::unix;
+        let mut status = [0u8; 4096]; // "/proc/self/status" is typically ~1.5 KB
+        let mut len = 0;
+        unsafe {
+            let fd = unix::open("/proc/self/status\0".as_ptr(), unix::O_RDONLY);
+            if fd >= 0 {
+                while len < status.len() {
+                    let read = unix::read(fd, status[len..].as_mut_ptr(), status.len() - len);
+                    if read <= 0 { break; }
+                    len += read as usize;
                 }
+                unix::close(fd);
             }
         }
+        for line in status[..len].split(|b| *b == b'\n') {
+            if let Some(state) = core::str::from_utf8(line).ok().and_then(parse_tracer_pid) { return state; }
+        }
     }
 
     State::Unknown
 }
 
+#[cfg(unix)]
+fn parse_tracer_pid(line: &str) -> Option<State> {
+    let line = line.trim();
+    if !line.starts_with("TracerPid:") { return None; }
+    match [" 0", "\t0", ":0"].iter().any(|zero| line.ends_with(zero)) {
+        true  => Some(State::Detatched),
+        false => Some(State::Attached),
+    }
+}
+
 #[test]
 fn state_examples() {
     use crate::debugger;
@@ -142,6 +155,7 @@ fn state_examples() {
 /// | OS X      | ???   | Untested, signal type might be wrong/suboptimal for debuggers
 /// | iOS       | ???   | Untested, signal type might be wrong/suboptimal for debuggers
 /// | WASM      | OK    |       |
+/// | Other     | ???   | Without an OS to ask, executes the architecture's breakpoint instruction (`int3`, `brk`, `bkpt`, `ebreak`) unconditionally.
 ///
 /// # Examples
 ///
@@ -151,6 +165,7 @@ fn state_examples() {
 /// debugger::break_if_attached();
 /// ```
 #[inline(always)] // We'd strongly prefer if the debugger showed us the call site, not this function.
+#[allow(clippy::needless_return)] // Each platform's block returns, the last platform's just happens to be last.
 pub fn break_if_attached() {
     #[cfg(windows)] unsafe {
         if crate::ffi::win32::IsDebuggerPresent() != 0 {
@@ -183,12 +198,29 @@ pub fn break_if_attached() {
     // like the "correct" signal.  https://en.wikipedia.org/wiki/Signal_(IPC)
     #[cfg(unix)] {
         if state() == State::Attached {
-            #[link(name = "c")] extern "C" { fn raise(signum: i32) -> i32; }
-            const SIGTRAP : i32 = 5;
-            unsafe { raise(SIGTRAP); }
+            unsafe { crate::ffi::unix::raise(crate::ffi::unix::SIGTRAP); }
         }
         return;
     }
+
+    // Bare metal and other targets without a platform API to lean on.  Like WASM's `debugger;`, we can't tell if a
+    // debugger is attached, so we trap unconditionally - presumably you're running under a debug probe.
+    #[cfg(not(any(windows, unix, target_arch = "wasm32")))] {
+        if state() != State::Detatched {
+            breakpoint();
+        }
+        return;
+    }
+}
+
+/// Execute the architecture's breakpoint instruction, if we know of one.
+#[cfg(not(any(windows, unix, target_arch = "wasm32")))]
+#[inline(always)]
+fn breakpoint() {
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]       unsafe { core::arch::asm!("int3") }
+    #[cfg(target_arch = "aarch64")]                                 unsafe { core::arch::asm!("brk #0xf000") }
+    #[cfg(target_arch = "arm")]                                     unsafe { core::arch::asm!("bkpt #0") }
+    #[cfg(any(target_arch = "riscv32", target_arch = "riscv64"))]   unsafe { core::arch::asm!("ebreak") }
 }
 
 /// Wait for a debugger to be attached to the current process.
@@ -229,6 +261,7 @@ pub fn break_if_attached() {
 ///     Err(m) => println!("Debugger didn't attach: {}", m),
 /// }
 /// ```
+#[cfg(feature = "std")]
 pub fn wait_until_attached<T: Into<Option<std::time::Duration>>> (timeout: T) -> Result<(), &'static str> {
     let timeout = timeout.into().map(|dur| std::time::Instant::now() + dur);
     loop {
@@ -247,6 +280,7 @@ pub fn wait_until_attached<T: Into<Option<std::time::Duration>>> (timeout: T) ->
     }
 }
 
+#[cfg(feature = "std")]
 #[test]
 fn wait_until_attached_examples() {
     use std::time::Duration;
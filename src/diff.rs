@@ -0,0 +1,143 @@
+//! Line diffs of `{:#?}` output, for failed equality checks.
+
+use crate::buffer::Buffer;
+use crate::output::{GREEN, RED, RESET};
+use alloc::vec::Vec;
+use core::fmt::{self, Debug, Display, Formatter};
+
+/// Don't bother allocating an LCS table for more line pairs than this.
+const MAX_CELLS : usize = 1 << 20;
+
+/// A longest-common-subsequence line diff between the `{:#?}` renderings of two values.
+pub(crate) struct Diff {
+    left:   Buffer,
+    right:  Buffer,
+    /// The number of lines the values share at their start, and at their end, which are left out of `lcs`
+    common: (usize, usize),
+    /// `lcs[i * (m+1) + j]` is the LCS length of `l[i..]` and `r[j..]`, where `l` and `r` are the `n` and `m` lines
+    /// between the common ones - or `None` if that table would be too large, in which case `l` and `r` are shown as
+    /// a single change.
+    lcs:    Option<Vec<u32>>,
+}
+
+impl Diff {
+    /// Returns `None` if neither value spans multiple lines, as there's nothing to gain over `{:?}`.
+    pub fn new(left: &dyn Debug, right: &dyn Debug) -> Option<Self> {
+        let left    = Buffer::format(format_args!("{:#?}", left));
+        let right   = Buffer::format(format_args!("{:#?}", right));
+        if left.is_truncated() || right.is_truncated() { return None; }
+        if !left.as_str().contains('\n') && !right.as_str().contains('\n') { return None; }
+
+        let l = left.as_str().lines().collect::<Vec<_>>();
+        let r = right.as_str().lines().collect::<Vec<_>>();
+        let prefix = l.iter().zip(&r).take_while(|(a, b)| a == b).count();
+        let suffix = l[prefix..].iter().rev().zip(r[prefix..].iter().rev()).take_while(|(a, b)| a == b).count();
+        let lcs = lcs(&l[prefix..l.len()-suffix], &r[prefix..r.len()-suffix]);
+        Some(Self { left, right, common: (prefix, suffix), lcs })
+    }
+
+    /// Display the diff, one line per line of either value - each preceded by `eol`, so it can follow another line
+    /// without a trailing separator - optionally colored with ANSI escapes.
+    pub fn display(&self, eol: &'static str, color: bool) -> impl Display + '_ {
+        DiffDisplay { diff: self, eol, color }
+    }
+}
+
+/// The LCS table of `l` and `r`, or `None` if it's too large (to allocate.)
+fn lcs(l: &[&str], r: &[&str]) -> Option<Vec<u32>> {
+    let (n, m) = (l.len(), r.len());
+    let cells = (n+1).checked_mul(m+1).filter(|&cells| cells <= MAX_CELLS)?;
+    let mut lcs = Vec::new();
+    lcs.try_reserve_exact(cells).ok()?;
+    lcs.resize(cells, 0);
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[i*(m+1)+j] = if l[i] == r[j] { lcs[(i+1)*(m+1)+j+1] + 1 } else { lcs[(i+1)*(m+1)+j].max(lcs[i*(m+1)+j+1]) };
+        }
+    }
+    Some(lcs)
+}
+
+struct DiffDisplay<'a> { diff: &'a Diff, eol: &'static str, color: bool }
+
+impl<'a> Display for DiffDisplay<'a> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let eol = self.eol;
+        let (red, green, reset) = if self.color { (RED, GREEN, RESET) } else { ("", "", "") };
+        let (prefix, suffix) = self.diff.common;
+        let mut l = self.diff.left.as_str().lines();
+        let mut r = self.diff.right.as_str().lines();
+        let n = l.clone().count() - prefix - suffix;
+        let m = r.clone().count() - prefix - suffix;
+
+        write!(f, "{}    Diff:       {}- left{}, {}+ right{}", eol, red, reset, green, reset)?;
+        for a in l.by_ref().take(prefix) { write!(f, "{}      {}", eol, a)?; }
+        r.by_ref().take(prefix).for_each(drop);
+
+        let (mut ml, mut mr) = (l.by_ref().take(n).peekable(), r.by_ref().take(m).peekable());
+        if let Some(lcs) = self.diff.lcs.as_ref() {
+            let lcs = |i: usize, j: usize| lcs[i*(m+1)+j];
+            let (mut i, mut j) = (0, 0);
+            loop {
+                match (ml.peek(), mr.peek()) {
+                    (Some(a), Some(b)) if a == b => {
+                        write!(f, "{}      {}", eol, a)?;
+                        ml.next(); mr.next(); i += 1; j += 1;
+                    },
+                    (Some(a), b) if b.is_none() || lcs(i+1, j) >= lcs(i, j+1) => {
+                        write!(f, "{}    {}- {}{}", eol, red, a, reset)?;
+                        ml.next(); i += 1;
+                    },
+                    (_, Some(b)) => {
+                        write!(f, "{}    {}+ {}{}", eol, green, b, reset)?;
+                        mr.next(); j += 1;
+                    },
+                    (_, None) => break, // (Some, None) is handled above
+                }
+            }
+        }
+        for a in ml { write!(f, "{}    {}- {}{}", eol, red, a, reset)?; }
+        for b in mr { write!(f, "{}    {}+ {}{}", eol, green, b, reset)?; }
+
+        for a in l { write!(f, "{}      {}", eol, a)?; }
+        Ok(())
+    }
+}
+
+#[test]
+fn diff_examples() {
+    use alloc::string::ToString;
+
+    #[allow(dead_code)] #[derive(Debug)] struct Point { x: i32, y: i32, z: i32 }
+    assert!(Diff::new(&1, &2).is_none()); // Single line values aren't worth diffing
+
+    let diff = Diff::new(&Point { x: 1, y: 2, z: 3 }, &Point { x: 1, y: 4, z: 3 }).unwrap();
+    assert_eq!(diff.display("\n", false).to_string(), concat!(
+        "\n    Diff:       - left, + right",
+        "\n      Point {",
+        "\n          x: 1,",
+        "\n    -     y: 2,",
+        "\n    +     y: 4,",
+        "\n          z: 3,",
+        "\n      }",
+    ));
+    assert!(diff.display("\n", true).to_string().contains("\x1B[31m-     y: 2,\x1B[0m"));
+}
+
+#[test]
+fn diff_long_values() {
+    use alloc::string::ToString;
+
+    // Only the one changed line is diffed, despite the ~2000 line values
+    let left    = (0..2000).collect::<Vec<u32>>();
+    let right   = (0..2000).map(|i| if i == 1000 { 0 } else { i }).collect::<Vec<u32>>();
+    let diff = Diff::new(&left, &right).unwrap().display("\n", false).to_string();
+    assert_eq!(diff.lines().count(), 1 + 1 + 2002 + 1); // "", "Diff:", each line, and the extra changed line
+    assert!(diff.contains("\n          999,\n    -     1000,\n    +     0,\n          1001,\n"), "{}", diff);
+
+    // Too many differing lines for an LCS table:  still a line diff, of everything between the first and last lines
+    let diff = Diff::new(&alloc::vec![1u8; 3000], &alloc::vec![2u8; 3000]).unwrap().display("\n", false).to_string();
+    let lines = diff.lines().collect::<Vec<_>>();
+    assert_eq!(lines.len(), 1 + 1 + 1 + 3000 + 3000 + 1);
+    assert_eq!((lines[2], lines[3], lines[3002], lines[3003], lines[6002], lines[6003]), ("      [", "    -     1,", "    -     1,", "    +     2,", "    +     2,", "      ]"));
+}
@@ -0,0 +1,181 @@
+use crate::{debugger, Bug};
+use crate::macro_impl::{self, DebugUnwrap};
+use core::fmt::{Debug, Display};
+use core::panic::Location;
+
+/// Method syntax for [unwrap!] and [expect!], for chaining in iterator and builder code.
+///
+/// Implemented for everything [unwrap!] works on:  `bool`, `Option`, `Result`, and raw pointers.  Like
+/// `Option::unwrap`, failures are reported at the *caller's* location, thanks to `#[track_caller]`.  Since there's no
+/// source expression to stringify, the "Expression:" line names the type instead.
+///
+/// # Examples
+///
+/// ```no_run
+/// use bugsalot::UnwrapOrBug;
+///
+/// let total : i32 = ["1", "2", "x"].iter()
+///     .map(|s| s.parse::<i32>().unwrap_or_bug(0))                     // Reports "x", continues with 0
+///     .sum();
+///
+/// let a : i32 = Some(42).unwrap_or_else_bug(|| 0);
+/// let b : i32 = None.expect_or_bug("Couldn't do something", 12);      // Reports, continues with 12
+/// let c : bool = false.unwrap_or_bug(true);                           // Reports, continues with true
+/// ```
+///
+/// [unwrap!]:  macro.unwrap.html
+/// [expect!]:  macro.expect.html
+pub trait UnwrapOrBug<O, E> : Sized {
+    /// Unwraps `self`, or reports a bug and returns `fallback`.
+    fn unwrap_or_bug(self, fallback: O) -> O;
+
+    /// Unwraps `self`, or reports a bug and returns `fallback()`.
+    fn unwrap_or_else_bug<F: FnOnce() -> O>(self, fallback: F) -> O;
+
+    /// Unwraps `self`, or reports a bug with `msg` and returns `fallback`.
+    fn expect_or_bug<M: Display>(self, msg: M, fallback: O) -> O;
+}
+
+impl<O, E: Debug, T: DebugUnwrap<O, E>> UnwrapOrBug<O, E> for T {
+    #[track_caller]
+    #[inline(always)] // We'd strongly prefer if the debugger showed us the call site, not this function.
+    fn unwrap_or_bug(self, fallback: O) -> O {
+        if self.can_unwrap() { return self.unwrap_ok(); }
+        macro_impl::log_unwrap_failed_at(Location::caller(), "unwrap_or_bug failed", core::any::type_name::<T>(), self, None);
+        debugger::break_if_attached();
+        fallback
+    }
+
+    #[track_caller]
+    #[inline(always)] // We'd strongly prefer if the debugger showed us the call site, not this function.
+    fn unwrap_or_else_bug<F: FnOnce() -> O>(self, fallback: F) -> O {
+        if self.can_unwrap() { return self.unwrap_ok(); }
+        macro_impl::log_unwrap_failed_at(Location::caller(), "unwrap_or_else_bug failed", core::any::type_name::<T>(), self, None);
+        debugger::break_if_attached();
+        fallback()
+    }
+
+    #[track_caller]
+    #[inline(always)] // We'd strongly prefer if the debugger showed us the call site, not this function.
+    fn expect_or_bug<M: Display>(self, msg: M, fallback: O) -> O {
+        if self.can_unwrap() { return self.unwrap_ok(); }
+        macro_impl::log_unwrap_failed_at(Location::caller(), msg, core::any::type_name::<T>(), self, None);
+        debugger::break_if_attached();
+        fallback
+    }
+}
+
+/// Report a bug if an `Option` is `None`, but pass it through either way.
+///
+/// # Examples
+///
+/// ```no_run
+/// use bugsalot::OptionBugExt;
+///
+/// let v = vec![1, 2, 3];
+/// let first = v.iter().find(|&&i| i > 5).bug_if_none().copied().unwrap_or(0); // Reports, continues with 0
+/// ```
+pub trait OptionBugExt : Sized {
+    /// Reports a bug if `self` is `None`, then returns `self` unmodified.
+    fn bug_if_none(self) -> Self;
+}
+
+impl<T> OptionBugExt for Option<T> {
+    #[track_caller]
+    #[inline(always)] // We'd strongly prefer if the debugger showed us the call site, not this function.
+    fn bug_if_none(self) -> Self {
+        if self.is_none() {
+            macro_impl::log_unwrap_failed_at(Location::caller(), "bug_if_none failed", core::any::type_name::<Self>(), self.as_ref(), None);
+            debugger::break_if_attached();
+        }
+        self
+    }
+}
+
+/// Report a bug if a `Result` is `Err`, but pass it through either way.
+///
+/// # Examples
+///
+/// ```no_run
+/// use bugsalot::{Bug, ResultBugExt};
+///
+/// let n : Result<i32, _> = "x".parse::<i32>().bug_if_err(); // Reports Err(ParseIntError { ... })
+///
+/// fn parse(s: &str) -> Result<i32, Bug<std::num::ParseIntError>> {
+///     Ok(s.parse::<i32>().bug_err()?) // Reports, then returns Err(Bug { ... })
+/// }
+/// ```
+pub trait ResultBugExt : Sized {
+    /// The `T` of `Result<T, E>`
+    type Ok;
+
+    /// The `E` of `Result<T, E>`
+    type Err;
+
+    /// Reports a bug if `self` is `Err(...)`, then returns `self` unmodified.
+    fn bug_if_err(self) -> Self;
+
+    /// Reports a bug if `self` is `Err(...)`, then returns `self` with the error wrapped in a [Bug].
+    ///
+    /// Like [try_bug!], the report includes the error's `source()` chain.
+    ///
+    /// [Bug]:      struct.Bug.html
+    /// [try_bug!]: macro.try_bug.html
+    fn bug_err(self) -> Result<Self::Ok, Bug<Self::Err>> where Self::Err: core::error::Error;
+}
+
+impl<T, E: Debug> ResultBugExt for Result<T, E> {
+    type Ok = T;
+    type Err = E;
+
+    #[track_caller]
+    #[inline(always)] // We'd strongly prefer if the debugger showed us the call site, not this function.
+    fn bug_if_err(self) -> Self {
+        if self.is_err() {
+            macro_impl::log_unwrap_failed_at(Location::caller(), "bug_if_err failed", core::any::type_name::<Self>(), self.as_ref(), None);
+            debugger::break_if_attached();
+        }
+        self
+    }
+
+    #[track_caller]
+    #[inline(always)] // We'd strongly prefer if the debugger showed us the call site, not this function.
+    fn bug_err(self) -> Result<T, Bug<E>> where E: core::error::Error {
+        if self.is_err() {
+            let as_error : macro_impl::AsErrorFn<&E> = |error| *error;
+            macro_impl::log_unwrap_failed_at(Location::caller(), "bug_err failed", core::any::type_name::<Self>(), self.as_ref(), Some(as_error));
+            debugger::break_if_attached();
+        }
+        let location = Location::caller();
+        self.map_err(|error| Bug::new(error, location))
+    }
+}
+
+#[test]
+fn ext_examples() {
+    let a : bool = true.unwrap_or_bug(false);
+    let b : i32 = Some(42).unwrap_or_bug(0);
+    let c : i32 = Ok::<i32, &str>(42).unwrap_or_else_bug(|| 0);
+    let d : i32 = Some(42).expect_or_bug("Couldn't do something", 0);
+    let e : *const i32 = (&42 as *const i32).unwrap_or_bug(core::ptr::null());
+    let f : Option<i32> = Some(42).bug_if_none();
+    let g : Result<i32, &str> = Ok(42).bug_if_err();
+    let h : Result<i32, Bug<core::num::ParseIntError>> = "x".parse::<i32>().bug_err();
+    assert!(a);
+    assert_eq!((b, c, d, f, g), (42, 42, 42, Some(42), Ok(42)));
+    assert_eq!(h.unwrap_err().into_inner(), "x".parse::<i32>().unwrap_err());
+    assert!(!e.is_null());
+}
+
+#[cfg(feature = "std")] #[test]
+fn bug_err_sources() {
+    #[derive(Debug)] struct ConfigError(core::num::ParseIntError);
+    impl Display for ConfigError { fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result { f.write_str("bad config") } }
+    impl core::error::Error for ConfigError { fn source(&self) -> Option<&(dyn core::error::Error + 'static)> { Some(&self.0) } }
+
+    let output = crate::output::capture(|| { let _ = Err::<(), _>(ConfigError("x".parse::<i32>().unwrap_err())).bug_err(); });
+    assert!(output.ends_with(concat!(
+        "    Error:      bad config\r\n",
+        "    Caused by:  invalid digit found in string\r\n",
+    )), "{:?}", output);
+}
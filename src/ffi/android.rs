@@ -1,6 +1,12 @@
+/// A logcat priority, as passed to `__android_log_write`.
+///
+/// bugsalot writes [debug!] and [debugln!] output as `DEBUG`, bug reports as `ERROR`, and panics as `FATAL`.
+/// Records written by `bugsalot::log::Logger` use the priority matching their level.
+///
+/// [debug!]:   ../../macro.debug.html
+/// [debugln!]: ../../macro.debugln.html
 #[repr(i32)]
-#[derive(Clone, Copy, Debug)]
-#[allow(dead_code)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum Priority {
     UNKNOWN   = 0,
     DEFAULT   = 1,
@@ -13,10 +19,11 @@ pub enum Priority {
     SILENT    = 8,
 }
 
-#[allow(non_camel_case_types)] type c_char = u8;
-#[allow(non_camel_case_types)] type c_int = i32;
+#[cfg(target_os = "android")] #[allow(non_camel_case_types)] type c_char = u8;
+#[cfg(target_os = "android")] #[allow(non_camel_case_types)] type c_int = i32;
 
+#[cfg(target_os = "android")]
 extern {
-    pub fn __android_log_print(priority: Priority, tag: *const c_char, fmt: *const c_char, ...) -> c_int;
-    pub fn __android_log_write(priority: Priority, tag: *const c_char, text: *const c_char) -> c_int;
+    pub(crate) fn __android_log_print(priority: Priority, tag: *const c_char, fmt: *const c_char, ...) -> c_int;
+    pub(crate) fn __android_log_write(priority: Priority, tag: *const c_char, text: *const c_char) -> c_int;
 }
@@ -1,3 +1,7 @@
-#[cfg(target_os="android")]     pub mod android;
-#[cfg(target_arch="wasm32")]    pub mod wasm;
-#[cfg(windows)]                 pub mod win32;
+//! Platform bindings.  Only [android::Priority] is public, for describing which logcat priority output is written at;
+//! the rest are for internal use.
+
+                                pub mod android; // Priority is available on every platform
+#[cfg(unix)]                    pub(crate) mod unix;
+#[cfg(target_arch="wasm32")]    pub(crate) mod wasm;
+#[cfg(windows)]                 pub(crate) mod win32;
@@ -0,0 +1,21 @@
+#[allow(non_camel_case_types)] type c_char = u8;
+#[allow(non_camel_case_types)] type c_int = i32;
+
+pub const O_RDONLY  : c_int = 0;
+pub const SIGTRAP   : c_int = 5;
+
+#[link(name = "c")]
+extern "C" {
+    pub fn raise(signum: c_int) -> c_int;
+    pub fn open(path: *const c_char, oflag: c_int, ...) -> c_int;
+    pub fn read(fd: c_int, buf: *mut u8, count: usize) -> isize;
+    #[allow(dead_code)] // Only used without `std`
+    pub fn write(fd: c_int, buf: *const u8, count: usize) -> isize;
+    pub fn close(fd: c_int) -> c_int;
+    #[allow(dead_code)] // Not used on Android
+    pub fn isatty(fd: c_int) -> c_int;
+    #[allow(dead_code)] // Not used on Android
+    pub fn getenv(name: *const c_char) -> *const c_char;
+    #[allow(dead_code)] // Only used without `std`
+    pub fn getpid() -> c_int;
+}
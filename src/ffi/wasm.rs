@@ -2,7 +2,7 @@
 
 #[cfg(feature = "stdweb")]
 pub mod console {
-    pub fn error(msg: String) {
+    pub fn error(msg: &str) {
         stdweb0::console!(error, msg);
     }
 }
@@ -12,11 +12,11 @@ pub mod console {
     use wasm_bindgen::prelude::*;
 
     #[wasm_bindgen] extern {
-        #[wasm_bindgen(js_namespace = console)] pub fn error(msg: String);
+        #[wasm_bindgen(js_namespace = console)] pub fn error(msg: &str);
     }
 }
 
 #[cfg(not(any(feature = "stdweb", feature="wasm-bindgen")))]
 pub mod console {
-    pub fn error(_msg: String) {}
+    pub fn error(_msg: &str) {}
 }
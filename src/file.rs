@@ -0,0 +1,177 @@
+//! Write all output to a size-rotated log file, in addition to the platform's debugging channels.  Requires `std`.
+//!
+//! Output is written with a single unbuffered `write` per report or [debug!] message, before any breakpoint is hit.
+//! Nothing is held in userspace buffers, so nothing is lost if the process is killed from a debugger.  For protection
+//! against power loss and kernel panics as well, enable [sync](struct.RotatingFile.html#method.sync).
+//!
+//! Output is written without the colors used on terminals, and with `\n` line endings (except on Windows.)
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use bugsalot::file::RotatingFile;
+//!
+//! RotatingFile::new("logs/server.log")
+//!     .max_bytes(10 << 20)    // Rotate after 10 MiB
+//!     .max_files(5)           // Keep server.log.1 ..= server.log.5
+//!     .install().unwrap();
+//!
+//! bugsalot::bug!("Written to logs/server.log, as well as stderr");
+//! ```
+//!
+//! [debug!]:   ../macro.debug.html
+
+use crate::buffer::Buffer;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static SINK : Mutex<Option<Sink>> = Mutex::new(None);
+
+/// Whether `SINK` is (probably) `Some`, so output can skip the lock when there's no file installed.
+static INSTALLED : AtomicBool = AtomicBool::new(false);
+
+/// Configuration for a size-rotated log file.
+///
+/// When writing a message would grow the file past [max_bytes](#method.max_bytes), `path` is renamed to `path.1`,
+/// `path.1` to `path.2`, and so on, discarding anything past [max_files](#method.max_files).
+#[derive(Clone, Debug)]
+pub struct RotatingFile {
+    path:       PathBuf,
+    max_bytes:  u64,
+    max_files:  usize,
+    sync:       bool,
+    #[cfg(feature = "gzip")] compress: bool,
+}
+
+impl RotatingFile {
+    /// Log to `path`, rotating after 10 MiB, and keeping 5 old files.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path:       path.into(),
+            max_bytes:  10 << 20,
+            max_files:  5,
+            sync:       false,
+            #[cfg(feature = "gzip")] compress: false,
+        }
+    }
+
+    /// Rotate before the file would exceed `max_bytes`.  A single message larger than this still gets a file of its own.
+    pub fn max_bytes(mut self, max_bytes: u64) -> Self { self.max_bytes = max_bytes; self }
+
+    /// Keep up to `max_files` old files (`path.1` ..= `path.N`).  With `0`, the file is simply truncated on rotation.
+    pub fn max_files(mut self, max_files: usize) -> Self { self.max_files = max_files; self }
+
+    /// Sync each message to disk (via `File::sync_data`) before returning.  Slow, but survives power loss.
+    pub fn sync(mut self, sync: bool) -> Self { self.sync = sync; self }
+
+    /// gzip old files on rotation, as `path.1.gz` etc.  Requires the `gzip` feature.
+    #[cfg(feature = "gzip")]
+    pub fn compress(mut self, compress: bool) -> Self { self.compress = compress; self }
+
+    /// Open the file for appending (creating it and its parent directories if necessary), and start writing output to
+    /// it, replacing any previously installed [RotatingFile].
+    pub fn install(self) -> io::Result<()> {
+        if let Some(dir) = self.path.parent() { if !dir.as_os_str().is_empty() { fs::create_dir_all(dir)?; } }
+        let file = OpenOptions::new().create(true).append(true).open(&self.path)?;
+        let len = file.metadata()?.len();
+        let mut sink = lock();
+        *sink = Some(Sink { config: self, file, len });
+        INSTALLED.store(true, Ordering::Relaxed);
+        Ok(())
+    }
+}
+
+/// Stop writing output to the installed [RotatingFile], if any, closing it.
+pub fn uninstall() {
+    let mut sink = lock();
+    *sink = None;
+    INSTALLED.store(false, Ordering::Relaxed);
+}
+
+struct Sink {
+    config: RotatingFile,
+    file:   File,
+    len:    u64,
+}
+
+fn lock() -> std::sync::MutexGuard<'static, Option<Sink>> {
+    SINK.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+pub(crate) fn write(message: &dyn std::fmt::Display) {
+    if !INSTALLED.load(Ordering::Relaxed) { return; }
+    let message = Buffer::format(format_args!("{}", message));
+    if let Some(sink) = lock().as_mut() { sink.write(message.as_str()); }
+}
+
+impl Sink {
+    fn write(&mut self, message: &str) {
+        let len = message.len() as u64;
+        if self.len > 0 && self.len + len > self.config.max_bytes {
+            let _ = self.rotate();
+        }
+        if self.file.write_all(message.as_bytes()).is_ok() { self.len += len; }
+        if self.config.sync { let _ = self.file.sync_data(); }
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        let path = &self.config.path;
+        let n = self.config.max_files;
+        if n > 0 {
+            let _ = fs::remove_file(self.rotated(n));
+            for i in (1..n).rev() { let _ = fs::rename(self.rotated(i), self.rotated(i+1)); }
+            fs::rename(path, numbered(path, 1))?;
+            #[cfg(feature = "gzip")] if self.config.compress { let _ = compress(&numbered(path, 1)); }
+        }
+        self.file = OpenOptions::new().create(true).write(true).truncate(true).open(path)?;
+        self.len = 0;
+        Ok(())
+    }
+
+    /// The name of the `i`th old file, e.g. `path.1` or `path.1.gz`
+    fn rotated(&self, i: usize) -> PathBuf {
+        let path = numbered(&self.config.path, i);
+        #[cfg(feature = "gzip")] if self.config.compress { return with_suffix(&path, ".gz"); }
+        path
+    }
+}
+
+fn numbered(path: &Path, i: usize) -> PathBuf { with_suffix(path, &format!(".{}", i)) }
+
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut path = path.as_os_str().to_owned();
+    path.push(suffix);
+    path.into()
+}
+
+/// Replace `path` with `path.gz`
+#[cfg(feature = "gzip")] fn compress(path: &Path) -> io::Result<()> {
+    let mut src = File::open(path)?;
+    let dst = File::create(with_suffix(path, ".gz"))?;
+    let mut gz = flate2::write::GzEncoder::new(dst, flate2::Compression::default());
+    io::copy(&mut src, &mut gz)?;
+    gz.finish()?.sync_all()?;
+    drop(src);
+    fs::remove_file(path)
+}
+
+#[test]
+fn rotating_file_examples() {
+    let dir = std::env::temp_dir().join(format!("bugsalot-rotating-file-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    let path = dir.join("test.log");
+
+    let config = RotatingFile::new(&path).max_bytes(10).max_files(2);
+    let mut sink = Sink { len: 0, file: { fs::create_dir_all(&dir).unwrap(); File::create(&path).unwrap() }, config };
+    for message in ["aaaa\n", "bbbb\n", "cccc\n", "dddd\n", "eeee\n", "ffff\n", "gggg\n"] { sink.write(message); }
+    drop(sink);
+
+    assert_eq!(fs::read_to_string(&path).unwrap(),                  "gggg\n");
+    assert_eq!(fs::read_to_string(numbered(&path, 1)).unwrap(),     "eeee\nffff\n");
+    assert_eq!(fs::read_to_string(numbered(&path, 2)).unwrap(),     "cccc\ndddd\n");
+    assert!(!numbered(&path, 3).exists());
+    let _ = fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,381 @@
+//! Configure the header written at the start of each report and each line of [debug!] output.
+//!
+//! By default, reports start with their site - e.g. `src/main.rs:12:5: ` - and [debug!] messages have no header.
+//! [set_template] replaces both with a template, which may include any of:
+//!
+//! | placeholder   | expands to |
+//! | ------------- | ---------- |
+//! | `{site}`      | The macro invocation's location, in the current [LocationStyle]
+//! | `{severity}`  | `ERROR` for bugs, failed unwraps, and panics, `DEBUG` for [debug!] and [debugln!]
+//! | `{pid}`       | The current process ID
+//! | `{thread}`    | The current thread's name, or its `ThreadId` if unnamed (requires `std`)
+//! | `{time}`      | Seconds since [set_template] was first called, e.g. `12.345678` (requires `std`)
+//! | `{utc}`       | The current UTC wall-clock time, e.g. `2020-01-02T03:04:05.678Z` (requires `std`)
+//! | `{{` / `}}`   | `{` / `}`
+//!
+//! Placeholders that are unavailable on the current target expand to nothing.
+//!
+//! # Examples
+//!
+//! ```
+//! bugsalot::header::set_template(Some("[{time}] {thread} {severity} {site}: "));
+//! bugsalot::debugln!("Written with a header");
+//! bugsalot::header::set_template(None);
+//! ```
+//!
+//! [debug!]:   ../macro.debug.html
+//! [debugln!]: ../macro.debugln.html
+
+use crate::macro_impl::Site;
+use alloc::boxed::Box;
+use core::fmt::{self, Display, Formatter, Write};
+use core::sync::atomic::{AtomicPtr, AtomicU8, Ordering};
+
+static TEMPLATE     : AtomicPtr<&'static str> = AtomicPtr::new(core::ptr::null_mut());
+static STYLE        : AtomicU8 = AtomicU8::new(STYLE_DEFAULT);
+#[cfg(feature = "std")] static HYPERLINKS : AtomicPtr<&'static std::path::Path> = AtomicPtr::new(core::ptr::null_mut());
+
+const STYLE_DEFAULT : u8 = 0;
+const STYLE_MSVC    : u8 = 1;
+const STYLE_GCC     : u8 = 2;
+
+/// How source locations are written.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum LocationStyle {
+    /// `file(line,column)`, as understood by Visual Studio's Output window.  The default on Windows.
+    Msvc,
+
+    /// `file:line:column`, as understood by GCC, VS Code problem matchers, Emacs' `compilation-mode`, and most
+    /// terminals' link detection.  The default everywhere else.
+    Gcc,
+}
+
+impl LocationStyle {
+    /// The default style for the current platform.
+    pub const PLATFORM : LocationStyle = if cfg!(windows) { LocationStyle::Msvc } else { LocationStyle::Gcc };
+
+    /// The current style, as set by [set_location_style], or else [PLATFORM](#associatedconstant.PLATFORM).
+    pub fn current() -> Self {
+        match STYLE.load(Ordering::Relaxed) {
+            STYLE_MSVC  => LocationStyle::Msvc,
+            STYLE_GCC   => LocationStyle::Gcc,
+            _           => LocationStyle::PLATFORM,
+        }
+    }
+
+    pub(crate) fn fmt_location(self, f: &mut Formatter, file: &str, line: u32, column: u32) -> fmt::Result {
+        match (self, column) {
+            (LocationStyle::Msvc, 0) => write!(f, "{}({})", file, line),
+            (LocationStyle::Msvc, _) => write!(f, "{}({},{})", file, line, column),
+            (LocationStyle::Gcc,  0) => write!(f, "{}:{}", file, line),
+            (LocationStyle::Gcc,  _) => write!(f, "{}:{}:{}", file, line, column),
+        }
+    }
+}
+
+/// Write source locations in `style`, or the platform's default style with `None`.
+///
+/// # Examples
+///
+/// ```
+/// use bugsalot::header::{set_location_style, LocationStyle};
+///
+/// set_location_style(Some(LocationStyle::Msvc)); // e.g. if running under Visual Studio on Linux
+/// bugsalot::bug!("Reported as file(line,column): ...");
+/// set_location_style(None);
+/// ```
+pub fn set_location_style(style: Option<LocationStyle>) {
+    STYLE.store(match style {
+        None                        => STYLE_DEFAULT,
+        Some(LocationStyle::Msvc)   => STYLE_MSVC,
+        Some(LocationStyle::Gcc)    => STYLE_GCC,
+    }, Ordering::Relaxed);
+}
+
+/// Make the sites in headers [OSC 8](https://gist.github.com/egmontkob/eb114294efbcd5adb1944c9f3cb5feda) terminal
+/// hyperlinks to the source file, or stop with `None`.  Disabled by default.
+///
+/// `base` is the directory relative paths are resolved against:  the root of the workspace, as `file!()` is relative
+/// to that - for a single package, `env!("CARGO_MANIFEST_DIR")`.  Absolute paths (e.g. of dependencies) are linked
+/// as-is.  Hyperlinks are only written when output is going to a terminal via stderr, as other channels (debuggers,
+/// logcat, custom callbacks) would show the raw escape codes.  Like [set_template], previous bases are leaked.
+/// Requires `std`.
+///
+/// # Examples
+///
+/// ```
+/// bugsalot::header::set_hyperlinks(Some(std::path::Path::new(env!("CARGO_MANIFEST_DIR"))));
+/// bugsalot::bug!("Reported with a link to this file, when written to a terminal");
+/// bugsalot::header::set_hyperlinks(None);
+/// ```
+#[cfg(feature = "std")]
+pub fn set_hyperlinks(base: Option<&'static std::path::Path>) {
+    let base = match base {
+        Some(base)  => Box::into_raw(Box::new(base)),
+        None        => core::ptr::null_mut(),
+    };
+    HYPERLINKS.store(base, Ordering::Release);
+}
+
+#[cfg(feature = "std")] fn hyperlinks() -> Option<&'static std::path::Path> {
+    let base = HYPERLINKS.load(Ordering::Acquire);
+    // SAFETY: only ever set from a leaked `Box<&'static Path>` by `set_hyperlinks`
+    if base.is_null() { None } else { Some(unsafe { *base }) }
+}
+
+/// Write `template` at the start of each report and each line of [debug!] output, or restore the default headers
+/// with `None`.
+///
+/// This is intended to be called once, at startup:  previous templates are leaked, as other threads might still be
+/// using them.
+///
+/// [debug!]:   ../macro.debug.html
+pub fn set_template(template: Option<&'static str>) {
+    #[cfg(feature = "std")] let _ = start();
+    let template = match template {
+        Some(template)  => Box::into_raw(Box::new(template)),
+        None            => core::ptr::null_mut(),
+    };
+    TEMPLATE.store(template, Ordering::Release);
+}
+
+fn template() -> Option<&'static str> {
+    let template = TEMPLATE.load(Ordering::Acquire);
+    // SAFETY: only ever set from a leaked `Box<&'static str>` by `set_template`
+    if template.is_null() { None } else { Some(unsafe { *template }) }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Severity { Debug, Error }
+
+impl Severity {
+    fn as_str(self) -> &'static str {
+        match self {
+            Severity::Debug => "DEBUG",
+            Severity::Error => "ERROR",
+        }
+    }
+}
+
+/// The header of a single report or [debug!] message, highlighting the site if `color`.
+///
+/// [debug!]:   ../macro.debug.html
+pub(crate) struct Header<'a> { severity: Severity, site: Option<&'a Site<'a>>, color: bool }
+
+impl<'a> Header<'a> {
+    pub fn debug(site: &'a Site<'a>, color: bool) -> Self { Self { severity: Severity::Debug, site: Some(site), color } }
+    pub fn error(site: &'a Site<'a>, color: bool) -> Self { Self { severity: Severity::Error, site: Some(site), color } }
+    #[allow(dead_code)] // Only used with `std`, by the panic hook
+    pub fn error_without_site() -> Self { Self { severity: Severity::Error, site: None, color: false } }
+}
+
+impl<'a> Display for Header<'a> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match (template(), self.severity, self.site) {
+            (Some(template), _, _)                  => self.fmt_template(template, f),
+            (None, Severity::Error, Some(site))     => write!(f, "{}: ", Hyperlink(site, self.color)),
+            (None, _, _)                            => Ok(()),
+        }
+    }
+}
+
+impl<'a> Header<'a> {
+    fn fmt_template(&self, template: &str, f: &mut Formatter) -> fmt::Result {
+        let mut rest = template;
+        while let Some(i) = rest.find(['{', '}']) {
+            f.write_str(&rest[..i])?;
+            rest = &rest[i..];
+            if rest.starts_with("{{") || rest.starts_with("}}") {
+                f.write_char(rest.as_bytes()[0] as char)?;
+                rest = &rest[2..];
+                continue;
+            }
+            if rest.starts_with('}') {
+                f.write_char('}')?;
+                rest = &rest[1..];
+                continue;
+            }
+            let end = match rest.find('}') { Some(end) => end, None => break };
+            match &rest[1..end] {
+                "site"      => if let Some(site) = self.site { write!(f, "{}", Hyperlink(site, self.color))?; },
+                "severity"  => f.write_str(self.severity.as_str())?,
+                "pid"       => fmt_pid(f)?,
+                "thread"    => fmt_thread(f)?,
+                "time"      => fmt_time(f)?,
+                "utc"       => fmt_utc(f)?,
+                _           => f.write_str(&rest[..=end])?, // Unknown placeholders are written as-is
+            }
+            rest = &rest[end+1..];
+        }
+        f.write_str(rest)
+    }
+}
+
+/// `args`, with `header` at the start of each line - including the first, if `start`.  A trailing newline doesn't start
+/// a line:  the header of whatever follows is written along with its text.
+pub(crate) struct LineHeaders<'a> { pub header: &'a dyn Display, pub args: fmt::Arguments<'a>, pub start: bool }
+
+impl<'a> Display for LineHeaders<'a> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        struct Writer<'a, 'f, 'g> { f: &'f mut Formatter<'g>, header: &'a dyn Display, start: bool }
+        impl<'a, 'f, 'g> Write for Writer<'a, 'f, 'g> {
+            fn write_str(&mut self, mut s: &str) -> fmt::Result {
+                while !s.is_empty() {
+                    if self.start { write!(self.f, "{}", self.header)?; }
+                    let (line, rest) = s.split_at(s.find('\n').map_or(s.len(), |newline| newline + 1));
+                    self.f.write_str(line)?;
+                    self.start = line.ends_with('\n');
+                    s = rest;
+                }
+                Ok(())
+            }
+        }
+        fmt::write(&mut Writer { f, header: self.header, start: self.start }, self.args)
+    }
+}
+
+/// A site, in bold if `color` (i.e. we're writing to a terminal), and wrapped in an OSC 8 hyperlink if
+/// [set_hyperlinks] is enabled too.
+struct Hyperlink<'a>(&'a Site<'a>, bool);
+
+impl<'a> Display for Hyperlink<'a> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let color = self.1;
+        let site = crate::output::Ansi { color, sgr: crate::output::BOLD, value: self.0 };
+        #[cfg(feature = "std")] if let Some(base) = hyperlinks().filter(|_| color) {
+            let file = std::path::Path::new(self.0.file);
+            let (base, file) = if file.is_absolute() { (file, None) } else { (base, Some(file)) };
+            f.write_str("\x1B]8;;file://")?;
+            if !base.starts_with("/") { f.write_char('/')?; } // e.g. file:///C:/...
+            fmt_url_path(f, base)?;
+            if let Some(file) = file {
+                if !matches!(base.as_os_str().as_encoded_bytes().last(), Some(b'/' | b'\\')) { f.write_char('/')?; }
+                fmt_url_path(f, file)?;
+            }
+            return write!(f, "\x1B\\{}\x1B]8;;\x1B\\", site);
+        }
+        Display::fmt(&site, f)
+    }
+}
+
+/// Write `path` percent-encoded for a `file://` URL, with `/` separators.
+#[cfg(feature = "std")] fn fmt_url_path(f: &mut Formatter, path: &std::path::Path) -> fmt::Result {
+    for &b in path.as_os_str().as_encoded_bytes() {
+        match b {
+            b'\\' => f.write_char('/')?,
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'/' | b'-' | b'_' | b'.' | b'~' | b':' => f.write_char(b as char)?,
+            _ => write!(f, "%{:02X}", b)?,
+        }
+    }
+    Ok(())
+}
+
+pub(crate) fn fmt_pid(f: &mut Formatter) -> fmt::Result {
+    #[cfg(feature = "std")] { write!(f, "{}", std::process::id()) }
+    #[cfg(all(not(feature = "std"), unix))] { write!(f, "{}", unsafe { crate::ffi::unix::getpid() }) }
+    #[cfg(all(not(feature = "std"), not(unix)))] { let _ = f; Ok(()) }
+}
+
+pub(crate) fn fmt_thread(f: &mut Formatter) -> fmt::Result {
+    #[cfg(feature = "std")] {
+        let thread = std::thread::current();
+        match thread.name() {
+            Some(name)  => f.write_str(name),
+            None        => write!(f, "{:?}", thread.id()),
+        }
+    }
+    #[cfg(not(feature = "std"))] { let _ = f; Ok(()) }
+}
+
+#[cfg(feature = "std")] fn start() -> std::time::Instant {
+    static START : std::sync::OnceLock<std::time::Instant> = std::sync::OnceLock::new();
+    *START.get_or_init(std::time::Instant::now)
+}
+
+fn fmt_time(f: &mut Formatter) -> fmt::Result {
+    #[cfg(feature = "std")] {
+        let elapsed = start().elapsed();
+        write!(f, "{}.{:06}", elapsed.as_secs(), elapsed.subsec_micros())
+    }
+    #[cfg(not(feature = "std"))] { let _ = f; Ok(()) }
+}
+
+pub(crate) fn fmt_utc(f: &mut Formatter) -> fmt::Result {
+    #[cfg(feature = "std")] {
+        let now = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap_or_default();
+        fmt_unix_time(f, now.as_secs(), now.subsec_millis())
+    }
+    #[cfg(not(feature = "std"))] { let _ = f; Ok(()) }
+}
+
+/// Formats seconds since the unix epoch as an RFC 3339 UTC timestamp.
+#[allow(dead_code)] // Only used with `std`
+fn fmt_unix_time(f: &mut Formatter, secs: u64, millis: u32) -> fmt::Result {
+    // Days to civil date:  https://howardhinnant.github.io/date_algorithms.html#civil_from_days
+    let days    = (secs / 86400) as i64 + 719468;
+    let era     = days.div_euclid(146097);
+    let doe     = days.rem_euclid(146097);
+    let yoe     = (doe - doe/1460 + doe/36524 - doe/146096) / 365;
+    let doy     = doe - (365*yoe + yoe/4 - yoe/100);
+    let mp      = (5*doy + 2) / 153;
+    let day     = doy - (153*mp + 2)/5 + 1;
+    let month   = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year    = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    let sod     = secs % 86400;
+    write!(f, "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z", year, month, day, sod / 3600, sod / 60 % 60, sod % 60, millis)
+}
+
+#[test]
+fn header_templates() {
+    use alloc::string::ToString;
+
+    struct UnixTime(u64, u32);
+    impl Display for UnixTime { fn fmt(&self, f: &mut Formatter) -> fmt::Result { fmt_unix_time(f, self.0, self.1) } }
+    assert_eq!(UnixTime(0, 0).to_string(),              "1970-01-01T00:00:00.000Z");
+    assert_eq!(UnixTime(1_577_934_245, 678).to_string(), "2020-01-02T03:04:05.678Z");
+
+    struct Template<'a>(&'static str, Header<'a>);
+    impl<'a> Display for Template<'a> { fn fmt(&self, f: &mut Formatter) -> fmt::Result { self.1.fmt_template(self.0, f) } }
+    let site = Site { file: "src/main.rs", line: 12, column: 5, module: None, expression: "" };
+    let s = site.to_string();
+    assert_eq!(Template("{severity} {site}: ", Header::error(&site, false)).to_string(),   alloc::format!("ERROR {}: ", s));
+    assert_eq!(Template("{severity} {site}: ", Header::debug(&site, false)).to_string(),   alloc::format!("DEBUG {}: ", s));
+    assert_eq!(Template("{{{site}}} {bogus} } {", Header::error(&site, false)).to_string(), alloc::format!("{{{}}} {{bogus}} }} {{", s));
+    assert_eq!(Template("{pid}", Header::error_without_site()).to_string(),         std::process::id().to_string());
+}
+
+#[test]
+fn line_headers() {
+    use alloc::string::ToString;
+    let lines = |text: &str, start: bool| LineHeaders { header: &"H ", args: format_args!("{}", text), start }.to_string();
+    assert_eq!(lines("a\nb\n\nc", true),   "H a\nH b\nH \nH c");
+    assert_eq!(lines("a\nb\n", false),      "a\nH b\n");
+    assert_eq!(lines("", true),             "");
+}
+
+#[test]
+fn location_styles() {
+    use alloc::string::ToString;
+
+    struct Location(LocationStyle, u32);
+    impl Display for Location { fn fmt(&self, f: &mut Formatter) -> fmt::Result { self.0.fmt_location(f, "src/main.rs", 12, self.1) } }
+    assert_eq!(Location(LocationStyle::Msvc, 5).to_string(), "src/main.rs(12,5)");
+    assert_eq!(Location(LocationStyle::Msvc, 0).to_string(), "src/main.rs(12)");
+    assert_eq!(Location(LocationStyle::Gcc,  5).to_string(), "src/main.rs:12:5");
+    assert_eq!(Location(LocationStyle::Gcc,  0).to_string(), "src/main.rs:12");
+}
+
+#[cfg(all(feature = "std", unix))] #[test]
+fn hyperlinks_to_files() {
+    use alloc::string::ToString;
+
+    let site        = Site { file: "src/main.rs", line: 12, column: 5, module: None, expression: "" };
+    let dependency  = Site { file: "/home/me/.cargo/registry/src/a b.rs", ..site };
+    set_hyperlinks(Some(std::path::Path::new("/work/my crate")));
+    let (link, dependency_link) = (Hyperlink(&site, true).to_string(), Hyperlink(&dependency, true).to_string());
+    set_hyperlinks(None);
+
+    assert_eq!(link, alloc::format!("\x1B]8;;file:///work/my%20crate/src/main.rs\x1B\\\x1B[1m{}\x1B[0m\x1B]8;;\x1B\\", site));
+    assert!(dependency_link.starts_with("\x1B]8;;file:///home/me/.cargo/registry/src/a%20b.rs\x1B\\"), "{:?}", dependency_link);
+    assert_eq!(Hyperlink(&site, false).to_string(), site.to_string());
+}
@@ -0,0 +1,150 @@
+//! Write reports as [JSON Lines](https://jsonlines.org/), for log shippers and test harnesses.  Requires `std`.
+//!
+//! If the `BUGSALOT_JSONL` environment variable is set when the first report is made, each report is also appended
+//! to the file it names - or, on unix, written to a file descriptor if it's of the form `fd:N` - as a single line
+//! containing one JSON object:
+//!
+//! ```json
+//! {"kind":"unwrap","site":{"file":"src/main.rs","line":12,"column":5,"module":"app"},"message":"unwrap! failed","expression":"a","expected":"Some(...)","found":"None","timestamp":"2020-01-02T03:04:05.678Z","pid":1234,"thread":"main"}
+//! ```
+//!
+//! | field         | present | value |
+//! | ------------- | ------- | ----- |
+//! | `kind`        | always  | `"bug"`, `"unwrap"`, `"check"`, or `"panic"`
+//! | `site`        | always  | `file`, `line`, and `column`, plus `module` if known - or `null` if unknown
+//! | `message`     | always  | The message, e.g. `"unwrap! failed"` or the message passed to [expect!] or [bug!]
+//! | `expression`  | unwrap, check | The stringified expression
+//! | `expected`    | unwrap  | e.g. `"Some(...)"`
+//! | `found`       | unwrap  | e.g. `"None"` or `"Err(\"reason\")"`
+//! | `left`        | check   | `Debug` of the left side of a failed [check_eq!] or [check_ne!]
+//! | `right`       | check   | `Debug` of the right side of a failed [check_eq!] or [check_ne!]
+//! | `timestamp`   | always  | UTC, e.g. `"2020-01-02T03:04:05.678Z"`
+//! | `pid`         | always  | The current process ID
+//! | `thread`      | always  | The current thread's name, or its `ThreadId` if unnamed
+//!
+//! [bug!]:         ../macro.bug.html
+//! [expect!]:      ../macro.expect.html
+//! [check_eq!]:    ../macro.check_eq.html
+//! [check_ne!]:    ../macro.check_ne.html
+
+use crate::macro_impl::Site;
+use std::fmt::{self, Debug, Display, Formatter, Write as _};
+use std::fs::{File, OpenOptions};
+use std::io::Write as _;
+use std::sync::{Mutex, OnceLock};
+
+/// The environment variable naming the file (or `fd:N`) to write reports to.
+pub const ENV_VAR : &str = "BUGSALOT_JSONL";
+
+/// A single report, as written to the JSON Lines sink.
+pub(crate) struct Report<'a> {
+    pub kind:       &'static str,
+    pub site:       Option<&'a Site<'a>>,
+    pub message:    &'a dyn Display,
+    pub expression: Option<&'a str>,
+    pub expected:   Option<&'a dyn Display>,
+    pub found:      Option<&'a dyn Display>,
+    pub operands:   Option<(&'a dyn Debug, &'a dyn Debug)>,
+}
+
+impl<'a> Report<'a> {
+    pub fn new(kind: &'static str, site: Option<&'a Site<'a>>, message: &'a dyn Display) -> Self {
+        Self { kind, site, message, expression: None, expected: None, found: None, operands: None }
+    }
+}
+
+/// Write `report` to the file or fd named by [ENV_VAR], if any.
+pub(crate) fn write(report: &Report) {
+    static SINK : OnceLock<Option<Mutex<File>>> = OnceLock::new();
+    let sink = match SINK.get_or_init(open) { Some(sink) => sink, None => return };
+    let line = format!("{}\n", report);
+    let mut file = sink.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    let _ = file.write_all(line.as_bytes());
+}
+
+fn open() -> Option<Mutex<File>> {
+    let target = std::env::var_os(ENV_VAR)?;
+    let target = target.to_string_lossy();
+
+    #[cfg(unix)] if let Some(fd) = target.strip_prefix("fd:") {
+        use std::os::unix::io::FromRawFd;
+        let fd = match fd.parse() { Ok(fd) => fd, Err(_) => { crate::macro_impl::output(crate::ffi::android::Priority::WARN, format_args!("bugsalot: invalid {}={:?}\r\n", ENV_VAR, target)); return None; } };
+        // SAFETY: the fd is owned by whoever set the environment variable.  We never close it, as the sink lives forever.
+        return Some(Mutex::new(unsafe { File::from_raw_fd(fd) }));
+    }
+
+    match OpenOptions::new().create(true).append(true).open(&*target) {
+        Ok(file)    => Some(Mutex::new(file)),
+        Err(err)    => { crate::macro_impl::output(crate::ffi::android::Priority::WARN, format_args!("bugsalot: unable to open {}={:?}: {}\r\n", ENV_VAR, target, err)); None },
+    }
+}
+
+impl<'a> Display for Report<'a> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{{\"kind\":{}", Json(&self.kind))?;
+        match self.site {
+            Some(site) => {
+                write!(f, ",\"site\":{{\"file\":{},\"line\":{},\"column\":{}", Json(&site.file), site.line, site.column)?;
+                if let Some(module) = site.module { write!(f, ",\"module\":{}", Json(&module))?; }
+                f.write_str("}")?;
+            },
+            None => f.write_str(",\"site\":null")?,
+        }
+        write!(f, ",\"message\":{}", Json(self.message))?;
+        if let Some(expression) = self.expression { write!(f, ",\"expression\":{}", Json(&expression))?; }
+        if let Some(expected) = self.expected { write!(f, ",\"expected\":{}", Json(expected))?; }
+        if let Some(found) = self.found { write!(f, ",\"found\":{}", Json(found))?; }
+        if let Some((left, right)) = self.operands { write!(f, ",\"left\":{},\"right\":{}", Json(&DebugStr(left)), Json(&DebugStr(right)))?; }
+        write!(f, ",\"timestamp\":{}", Json(&FmtFn(crate::header::fmt_utc)))?;
+        write!(f, ",\"pid\":{}", FmtFn(crate::header::fmt_pid))?;
+        write!(f, ",\"thread\":{}}}", Json(&FmtFn(crate::header::fmt_thread)))
+    }
+}
+
+/// A `Display`ed value, as a quoted and escaped JSON string.
+struct Json<'a>(&'a dyn Display);
+
+impl<'a> Display for Json<'a> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_char('"')?;
+        write!(Escape(f), "{}", self.0)?;
+        f.write_char('"')
+    }
+}
+
+struct Escape<'a, 'b>(&'a mut Formatter<'b>);
+
+impl<'a, 'b> fmt::Write for Escape<'a, 'b> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for c in s.chars() {
+            match c {
+                '"'             => self.0.write_str("\\\"")?,
+                '\\'            => self.0.write_str("\\\\")?,
+                '\n'            => self.0.write_str("\\n")?,
+                '\r'            => self.0.write_str("\\r")?,
+                '\t'            => self.0.write_str("\\t")?,
+                c if c < ' '    => write!(self.0, "\\u{:04x}", c as u32)?,
+                c               => self.0.write_char(c)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+struct DebugStr<'a>(&'a dyn Debug);
+impl<'a> Display for DebugStr<'a> { fn fmt(&self, f: &mut Formatter) -> fmt::Result { write!(f, "{:?}", self.0) } }
+
+struct FmtFn(fn(&mut Formatter) -> fmt::Result);
+impl Display for FmtFn { fn fmt(&self, f: &mut Formatter) -> fmt::Result { (self.0)(f) } }
+
+#[test]
+fn jsonl_reports() {
+    let site = Site { file: "src\\main.rs", line: 12, column: 5, module: Some("app"), expression: "a" };
+    let report = Report { expression: Some("a"), expected: Some(&"Some(...)"), found: Some(&"None"), ..Report::new("unwrap", Some(&site), &"unwrap! \"failed\"\n") };
+    let line = report.to_string();
+    assert!(line.starts_with(r#"{"kind":"unwrap","site":{"file":"src\\main.rs","line":12,"column":5,"module":"app"},"message":"unwrap! \"failed\"\n","expression":"a","expected":"Some(...)","found":"None","timestamp":""#), "{}", line);
+    assert!(line.ends_with(&format!(r#","pid":{},"thread":"jsonl::jsonl_reports"}}"#, std::process::id())), "{}", line);
+
+    let report = Report { operands: Some((&1, &"\u{1}")), ..Report::new("check", None, &"check_eq! failed") };
+    assert!(report.to_string().contains(r#""site":null,"message":"check_eq! failed","left":"1","right":"\"\\u{1}\"""#));
+}
@@ -1,10 +1,37 @@
 // TODO: Module level docs.
 
-mod ffi;
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+extern crate alloc;
+
+mod bug;
+mod buffer;
+mod diff;
+mod ext;
+pub mod ffi;
+#[cfg(feature = "std")] pub mod file;
+#[cfg(feature = "std")] mod panic;
 pub mod debugger;
+pub mod header;
+#[cfg(feature = "std")] pub mod jsonl;
+#[cfg(feature = "std")] pub mod recorder;
+pub mod output;
+#[cfg(feature = "log")] pub mod log;
+#[cfg(feature = "tracing")] pub mod tracing;
+
+pub use bug::Bug;
+pub use ext::{UnwrapOrBug, OptionBugExt, ResultBugExt};
+pub use macro_impl::DebugUnwrap;
+#[cfg(feature = "derive")] pub use bugsalot_derive::DebugUnwrap;
+#[cfg(all(test, feature = "derive"))] extern crate self as bugsalot; // For #[derive(DebugUnwrap)] in tests
+#[cfg(feature = "std")] pub use panic::install_panic_hook;
 
 #[doc(hidden)] pub mod macro_impl {
-    use std::fmt::{self, Debug, Display, Formatter};
+    use crate::diff::Diff;
+    use crate::ffi::android::Priority;
+    use crate::header::Header;
+    use crate::output::Ansi;
+    use core::fmt::{self, Debug, Display, Formatter};
+    use core::panic::Location;
 
     pub trait MaybeDebug { fn fmt(&self, f: &mut Formatter) -> fmt::Result { Display::fmt("???", f) } }
     impl<T: Debug> MaybeDebug for T { fn fmt(&self, f: &mut Formatter) -> fmt::Result { self.fmt(f) } }
@@ -12,11 +39,90 @@ pub mod debugger;
     struct MaybeDebugToDebug<'a>(&'a dyn MaybeDebug);
     impl<'a> Debug for MaybeDebugToDebug<'a> { fn fmt(&self, f: &mut Formatter) -> fmt::Result { self.0.fmt(f) } }
 
+    /// The "Expected:" value of a failed unwrap, e.g. `Some(...)`
+    struct Expected { pass: &'static str, pass_parens: bool }
+    impl Display for Expected { fn fmt(&self, f: &mut Formatter) -> fmt::Result { write!(f, "{}{}", self.pass, if self.pass_parens { "(...)" } else { "" }) } }
+
+    /// Autoref specialization to detect `Result<_, E: Error>` at the call site, where we still know the concrete
+    /// type, so we can list the error's `source()` chain.  See:
+    /// <https://github.com/dtolnay/case-studies/blob/master/autoref-specialization/README.md>
+    pub struct ErrorProbe<'a, T>(pub &'a T);
+    pub type AsErrorFn<E> = fn(&E) -> &dyn core::error::Error;
+
+    pub trait ErrorProbeResult<E> { fn as_error_fn(&self) -> Option<AsErrorFn<E>>; }
+    impl<'a, R, E: core::error::Error> ErrorProbeResult<E> for ErrorProbe<'a, Result<R, E>> {
+        fn as_error_fn(&self) -> Option<AsErrorFn<E>> { Some(|e| e) }
+    }
+
+    pub trait ErrorProbeOther { fn as_error_fn<E>(&self) -> Option<AsErrorFn<E>> { None } }
+    impl<'a, T> ErrorProbeOther for &ErrorProbe<'a, T> {}
+
+    /// An error's `Display`, and that of each of its `source()`s, one per line, each preceded by the separator.
+    struct Causes<'a>(Option<&'a dyn core::error::Error>, &'static str);
+    impl<'a> Display for Causes<'a> {
+        fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+            let mut error = match self.0 { Some(e) => e, None => return Ok(()) };
+            write!(f, "{}    Error:      {}", self.1, error)?;
+            while let Some(source) = error.source() {
+                write!(f, "{}    Caused by:  {}", self.1, source)?;
+                error = source;
+            }
+            Ok(())
+        }
+    }
+
+    /// The "Found:" value of a failed unwrap, e.g. `Err("reason")`
+    struct Found<'a> { fail: &'static str, fail_parens: bool, err: &'a dyn MaybeDebug }
+    impl<'a> Display for Found<'a> {
+        fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+            if self.fail.is_empty() { write!(f, "{:?}", MaybeDebugToDebug(self.err)) }
+            else if self.fail_parens { write!(f, "{}({:?})", self.fail, MaybeDebugToDebug(self.err)) }
+            else { f.write_str(self.fail) }
+        }
+    }
+
+    /// Types that [unwrap!] and [expect!] (and [UnwrapOrBug]) can unwrap into an `O`, or fail with an `E`.
+    ///
+    /// For enums, prefer `#[derive(DebugUnwrap)]` (requires the `derive` feature), marking passing variants with
+    /// `#[ok]` and/or failing variants with `#[err]`:
+    ///
+    /// ```
+    /// # #[cfg(feature = "derive")] {
+    /// use bugsalot::{unwrap, DebugUnwrap};
+    ///
+    /// #[derive(Debug, DebugUnwrap)]
+    /// enum LoadResult {
+    ///     #[ok] Loaded(u32),
+    ///     #[ok] Cached,
+    ///     NotFound(&'static str),
+    ///     Corrupt,
+    /// }
+    ///
+    /// let _ = unwrap!(LoadResult::Loaded(42));
+    /// let _ = unwrap!(LoadResult::NotFound("a.png"), LoadResult::Cached);
+    /// // Expected:   Loaded | Cached
+    /// // Found:      NotFound("a.png")
+    /// # }
+    /// ```
+    ///
+    /// [unwrap!]:      macro.unwrap.html
+    /// [expect!]:      macro.expect.html
+    /// [UnwrapOrBug]:  trait.UnwrapOrBug.html
     pub trait DebugUnwrap<O, E : fmt::Debug> {
-        /// Returns (pass, fail, pass_parens, fail_parens)
+        /// Returns `(pass, fail, pass_parens, fail_parens)`, the variant names to report for "Expected:" and
+        /// "Found:", and whether to follow them with `(...)` or `(`[unwrap_err]`)` respectively.  An empty `fail`
+        /// reports just the `Debug` of [unwrap_err], for types whose `Debug` already names the variant.
+        ///
+        /// [unwrap_err]:   #tymethod.unwrap_err
         fn get_pass_fail_strs(&self) -> (&'static str, &'static str, bool, bool);
+
+        /// Returns `true` if `self` passes.
         fn can_unwrap(&self) -> bool;
+
+        /// The unwrapped value.  Only called if [can_unwrap](#tymethod.can_unwrap) returned `true`.
         fn unwrap_ok(self) -> O;
+
+        /// The value to report.  Only called if [can_unwrap](#tymethod.can_unwrap) returned `false`.
         fn unwrap_err(self) -> E;
     }
 
@@ -29,14 +135,14 @@ pub mod debugger;
 
     impl<T> DebugUnwrap<*const T, *const T> for *const T {
         fn get_pass_fail_strs(&self) -> (&'static str, &'static str, bool, bool) { ("non-null", "null", false, false) }
-        fn can_unwrap(&self) -> bool { *self != 0 as *const T }
+        fn can_unwrap(&self) -> bool { !self.is_null() }
         fn unwrap_ok(self) -> *const T { self }
         fn unwrap_err(self) -> *const T { self }
     }
 
     impl<T> DebugUnwrap<*mut T, *mut T> for *mut T {
         fn get_pass_fail_strs(&self) -> (&'static str, &'static str, bool, bool) { ("non-null", "null", false, false) }
-        fn can_unwrap(&self) -> bool { *self != 0 as *mut T }
+        fn can_unwrap(&self) -> bool { !self.is_null() }
         fn unwrap_ok(self) -> *mut T { self }
         fn unwrap_err(self) -> *mut T { self }
     }
@@ -45,7 +151,7 @@ pub mod debugger;
         fn get_pass_fail_strs(&self) -> (&'static str, &'static str, bool, bool) { ("Some", "None", true, false) }
         fn can_unwrap(&self) -> bool { self.is_some() }
         fn unwrap_ok(self) -> T { if let Some(r) = self { r } else { unreachable!() } }
-        fn unwrap_err(self) -> () { assert!(self.is_none()); () }
+        fn unwrap_err(self) { assert!(self.is_none()); }
     }
 
     impl<R,E: fmt::Debug> DebugUnwrap<R,E> for Result<R,E> {
@@ -55,70 +161,282 @@ pub mod debugger;
         fn unwrap_err(self) -> E { if let Err(e) = self { e } else { unreachable!() } }
     }
 
-    #[allow(dead_code)]
-    fn require_nul(mut message: String) -> String {
-        if !message.ends_with("\0") { message.push('\0'); }
-        message
+    impl<T> DebugUnwrap<T,()> for core::task::Poll<T> {
+        fn get_pass_fail_strs(&self) -> (&'static str, &'static str, bool, bool) { ("Ready", "Pending", true, false) }
+        fn can_unwrap(&self) -> bool { self.is_ready() }
+        fn unwrap_ok(self) -> T { if let core::task::Poll::Ready(r) = self { r } else { unreachable!() } }
+        fn unwrap_err(self) { assert!(self.is_pending()); }
+    }
+
+    impl<B: fmt::Debug, C> DebugUnwrap<C,B> for core::ops::ControlFlow<B,C> {
+        fn get_pass_fail_strs(&self) -> (&'static str, &'static str, bool, bool) { ("Continue", "Break", true, true) }
+        fn can_unwrap(&self) -> bool { self.is_continue() }
+        fn unwrap_ok(self) -> C { if let core::ops::ControlFlow::Continue(c) = self { c } else { unreachable!() } }
+        fn unwrap_err(self) -> B { if let core::ops::ControlFlow::Break(b) = self { b } else { unreachable!() } }
+    }
+
+    /// Fails with the exit code or (on unix) signal number.
+    #[cfg(feature = "std")]
+    impl DebugUnwrap<std::process::ExitStatus, i32> for std::process::ExitStatus {
+        fn get_pass_fail_strs(&self) -> (&'static str, &'static str, bool, bool) {
+            if self.code().is_some() { return ("success", "exit code", false, true); }
+            #[cfg(unix)] { if std::os::unix::process::ExitStatusExt::signal(self).is_some() { return ("success", "signal", false, true); } }
+            ("success", "unknown failure", false, false)
+        }
+        fn can_unwrap(&self) -> bool { self.success() }
+        fn unwrap_ok(self) -> std::process::ExitStatus { self }
+        fn unwrap_err(self) -> i32 {
+            #[cfg(unix)] { if let Some(signal) = std::os::unix::process::ExitStatusExt::signal(&self) { return signal; } }
+            self.code().unwrap_or(0)
+        }
+    }
+
+    /// Fails on NaN or infinity.
+    impl DebugUnwrap<f32, f32> for f32 {
+        fn get_pass_fail_strs(&self) -> (&'static str, &'static str, bool, bool) { ("finite", nonfinite_str(self.is_nan(), self.is_sign_negative()), false, false) }
+        fn can_unwrap(&self) -> bool { self.is_finite() }
+        fn unwrap_ok(self) -> f32 { self }
+        fn unwrap_err(self) -> f32 { self }
+    }
+
+    /// Fails on NaN or infinity.
+    impl DebugUnwrap<f64, f64> for f64 {
+        fn get_pass_fail_strs(&self) -> (&'static str, &'static str, bool, bool) { ("finite", nonfinite_str(self.is_nan(), self.is_sign_negative()), false, false) }
+        fn can_unwrap(&self) -> bool { self.is_finite() }
+        fn unwrap_ok(self) -> f64 { self }
+        fn unwrap_err(self) -> f64 { self }
+    }
+
+    fn nonfinite_str(nan: bool, negative: bool) -> &'static str {
+        match (nan, negative) {
+            (true,  _    ) => "NaN",
+            (false, false) => "inf",
+            (false, true ) => "-inf",
+        }
+    }
+
+    pub fn output(priority: Priority, args: fmt::Arguments) {
+        crate::output::write(priority, args);
+    }
+
+    pub fn output_debug(site: &CallSite, args: fmt::Arguments) {
+        let site = Site::from(site);
+        crate::output::write_debug(&Header::debug(&site, crate::output::is_color_stderr()), args);
+    }
+
+    /// Static information about a macro's call site.  Each macro invocation emits one of these as a `static`, and
+    /// passes a single pointer to it, to minimize the codegen needed at each call site.
+    pub struct CallSite {
+        /// `concat!(file!(), "\0", module_path!(), "\0", expression)`, packed into a single `&str` so each site
+        /// needs only a single pointer (and thus relocation) instead of three.
+        pub strings:    &'static str,
+        pub line:       u32,
+        pub column:     u32,
+    }
+
+    impl CallSite {
+        pub fn file(&self) -> &'static str { self.strings.split('\0').next().unwrap_or("") }
+        pub fn module(&self) -> &'static str { self.strings.split('\0').nth(1).unwrap_or("") }
+        /// The stringified expression being unwrapped, or `""` for [bug!](../macro.bug.html)
+        pub fn expression(&self) -> &'static str { self.strings.splitn(3, '\0').nth(2).unwrap_or("") }
+    }
+
+    /// Where a report came from:  either a macro's static [CallSite], or a `#[track_caller]` [Location].
+    #[derive(Clone, Copy)]
+    pub struct Site<'a> {
+        pub file:       &'a str,
+        pub line:       u32,
+        pub column:     u32,
+        /// `None` for [Location]s, which don't know what module they're in.
+        pub module:     Option<&'a str>,
+        /// The stringified expression being unwrapped, or `""` for bugs
+        pub expression: &'a str,
+    }
+
+    impl<'a> Site<'a> {
+        pub fn from_location(location: &'a Location<'a>, expression: &'a str) -> Self {
+            Self { file: location.file(), line: location.line(), column: location.column(), module: None, expression }
+        }
     }
 
-    #[allow(dead_code)]
-    fn require_no_nul(mut message: String) -> String {
-        if message.ends_with("\0") { message.pop(); }
-        message
+    impl From<&CallSite> for Site<'static> {
+        fn from(site: &CallSite) -> Self {
+            Self { file: site.file(), line: site.line, column: site.column, module: Some(site.module()), expression: site.expression() }
+        }
+    }
+
+    /// `file(line,column)` or `file:line:column`, depending on the current [LocationStyle](../header/enum.LocationStyle.html).
+    impl<'a> Display for Site<'a> {
+        fn fmt(&self, f: &mut Formatter) -> fmt::Result { crate::header::LocationStyle::current().fmt_location(f, self.file, self.line, self.column) }
     }
 
-    pub fn output(message: String) {
-        #[allow(unused_imports)] use crate::ffi::*;
-        #[allow(unused_unsafe)] unsafe {
-            #[cfg(windows)] win32::OutputDebugStringA(require_nul(message).as_ptr());
-            #[cfg(target_os = "android")] android::__android_log_write(android::Priority::ERROR, "bugsalot\0".as_ptr(), require_nul(message).as_ptr());
+    /// Adds a `Module:` line to a report, if the module is known.
+    struct ModuleLine<'a>(Option<&'a str>, &'static str);
+    impl<'a> Display for ModuleLine<'a> {
+        fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+            match self.0 {
+                Some(module)    => write!(f, "    Module:     {}{}", module, self.1),
+                None            => Ok(()),
+            }
         }
+    }
 
-        #[cfg(target_arch = "wasm32")] wasm::console::error(require_no_nul(message));
-        #[cfg(all(unix, not(target_os = "android")))] eprint!("{}", require_no_nul(message));
+    #[cold] #[inline(never)]
+    pub fn log_unwrap_failed<M: Display, O, E: fmt::Debug, DU: DebugUnwrap<O, E>>(site: &CallSite, msg: M, du: DU, as_error: Option<AsErrorFn<E>>) {
+        let (pass, fail, pass_parens, fail_parens) = du.get_pass_fail_strs();
+        let err = du.unwrap_err();
+        let error = as_error.map(|as_error| as_error(&err));
+        report_unwrap_failed(&site.into(), &msg, &Expected { pass, pass_parens }, &Found { fail, fail_parens, err: &err }, error);
     }
 
-    // TODO: Consider abusing const/static structs to minimize the amount of codegen we need at each call site just to initialize argument registers.
-    pub fn log_unwrap_failed<M: std::fmt::Display, O, E: fmt::Debug, DU: DebugUnwrap<O, E>>(file: &str, line: u32, msg: M, expr: &str, du: DU) {
+    #[cold] #[inline(never)]
+    pub fn log_unwrap_failed_at<M: Display, O, E: fmt::Debug, DU: DebugUnwrap<O, E>>(location: &Location, msg: M, expression: &str, du: DU, as_error: Option<AsErrorFn<E>>) {
         let (pass, fail, pass_parens, fail_parens) = du.get_pass_fail_strs();
         let err = du.unwrap_err();
-        if fail_parens {
-            output(format!(
-                concat!(
-                    "{}({}): {}\r\n",
-                    "    Expression: {}\r\n",
-                    "    Expected:   {}{}\r\n",
-                    "    Found:      {}({:?})\r\n\0",
-                ),
-                file, line, msg,
-                expr,
-                pass, if pass_parens { "(...)" } else { "" },
-                fail, MaybeDebugToDebug(&err)
-            ));
-        } else {
-            output(format!(
-                concat!(
-                    "{}({}): {}\r\n",
-                    "    Expression: {}\r\n",
-                    "    Expected:   {}{}\r\n",
-                    "    Found:      {}\r\n\0",
-                ),
-                file, line, msg,
-                expr,
-                pass, if pass_parens { "(...)" } else { "" },
-                fail
-            ));
+        let error = as_error.map(|as_error| as_error(&err));
+        report_unwrap_failed(&Site::from_location(location, expression), &msg, &Expected { pass, pass_parens }, &Found { fail, fail_parens, err: &err }, error);
+    }
+
+    #[cold] #[inline(never)]
+    fn report_unwrap_failed(site: &Site, msg: &dyn Display, expected: &dyn Display, found: &dyn Display, error: Option<&dyn core::error::Error>) {
+        let _lock = crate::output::ReportLock::lock();
+        crate::output::flush();
+        #[cfg(feature = "std")] crate::recorder::dump();
+        let color = crate::output::is_color_stderr();
+        output(Priority::ERROR, format_args!(
+            concat!(
+                "{}{}\r\n",
+                "{}",
+                "    Expression: {}\r\n",
+                "    Expected:   {}\r\n",
+                "    Found:      {}{}\r\n",
+            ),
+            Header::error(site, color), msg,
+            ModuleLine(site.module, "\r\n"),
+            Ansi { color, sgr: crate::output::DIM, value: site.expression },
+            expected,
+            Ansi { color, sgr: crate::output::RED, value: found },
+            Causes(error, "\r\n"),
+        ));
+
+        #[cfg(feature = "log")] crate::log::forward(site, format_args!(
+            concat!(
+                "{}\n",
+                "    Expression: {}\n",
+                "    Expected:   {}\n",
+                "    Found:      {}{}",
+            ),
+            msg,
+            site.expression,
+            expected,
+            found,
+            Causes(error, "\n"),
+        ));
+
+        #[cfg(feature = "std")] crate::jsonl::write(&crate::jsonl::Report {
+            expression: Some(site.expression), expected: Some(expected), found: Some(found),
+            ..crate::jsonl::Report::new("unwrap", Some(site), msg)
+        });
+        #[cfg(feature = "tracing")] crate::tracing::unwrap_failed(site, msg, expected, found);
+    }
+
+    /// The "Left:" and "Right:" values of a failed `check_eq!` or `check_ne!`, if any - or a line diff of the two.
+    /// Each line is preceded by `eol`.
+    struct Operands<'a> { operands: Option<(&'a dyn Debug, &'a dyn Debug)>, diff: Option<&'a Diff>, eol: &'static str, color: bool }
+    impl<'a> Display for Operands<'a> {
+        fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+            if let Some(diff) = self.diff { return Display::fmt(&diff.display(self.eol, self.color), f); }
+            let (left, right) = match self.operands { Some(operands) => operands, None => return Ok(()) };
+            write!(f, "{}    Left:       {:?}", self.eol, left)?;
+            write!(f, "{}    Right:      {:?}", self.eol, right)
         }
     }
 
-    pub fn log_bug(file: &str, line: u32, msg: impl std::fmt::Display) {
-        output(format!(
-            "{}({}): {}\r\n",
-            file, line, msg
+    #[cold] #[inline(never)]
+    pub fn log_check_failed(site: &CallSite, msg: fmt::Arguments) {
+        report_check_failed(&site.into(), &msg, None, None);
+    }
+
+    #[cold] #[inline(never)]
+    pub fn log_check_eq_failed(site: &CallSite, msg: fmt::Arguments, left: &dyn Debug, right: &dyn Debug) {
+        let diff = Diff::new(left, right);
+        report_check_failed(&site.into(), &msg, Some((left, right)), diff.as_ref());
+    }
+
+    #[cold] #[inline(never)]
+    pub fn log_check_ne_failed(site: &CallSite, msg: fmt::Arguments, left: &dyn Debug, right: &dyn Debug) {
+        report_check_failed(&site.into(), &msg, Some((left, right)), None);
+    }
+
+    #[cold] #[inline(never)]
+    fn report_check_failed(site: &Site, msg: &dyn Display, operands: Option<(&dyn Debug, &dyn Debug)>, diff: Option<&Diff>) {
+        let _lock = crate::output::ReportLock::lock();
+        crate::output::flush();
+        #[cfg(feature = "std")] crate::recorder::dump();
+        let color = crate::output::is_color_stderr();
+        output(Priority::ERROR, format_args!(
+            "{}{}\r\n{}    Expression: {}{}\r\n",
+            Header::error(site, color), msg,
+            ModuleLine(site.module, "\r\n"),
+            Ansi { color, sgr: crate::output::DIM, value: site.expression },
+            Operands { operands, diff, eol: "\r\n", color },
+        ));
+
+        #[cfg(feature = "log")] crate::log::forward(site, format_args!("{}\n    Expression: {}{}", msg, site.expression, Operands { operands, diff, eol: "\n", color: false }));
+        #[cfg(feature = "std")] crate::jsonl::write(&crate::jsonl::Report {
+            expression: Some(site.expression), operands,
+            ..crate::jsonl::Report::new("check", Some(site), msg)
+        });
+        #[cfg(feature = "tracing")] crate::tracing::check_failed(site, msg, operands);
+    }
+
+    #[cold] #[inline(never)]
+    pub fn log_bug(site: &CallSite, msg: impl Display) {
+        report_bug(&site.into(), &msg);
+    }
+
+    #[cold] #[inline(never)]
+    pub fn log_bug_at(location: &Location, msg: impl Display) {
+        report_bug(&Site::from_location(location, ""), &msg);
+    }
+
+    #[cold] #[inline(never)]
+    fn report_bug(site: &Site, msg: &dyn Display) {
+        let _lock = crate::output::ReportLock::lock();
+        crate::output::flush();
+        #[cfg(feature = "std")] crate::recorder::dump();
+        let color = crate::output::is_color_stderr();
+        output(Priority::ERROR, format_args!(
+            "{}{}\r\n{}",
+            Header::error(site, color), msg,
+            ModuleLine(site.module, "\r\n")
         ));
+
+        #[cfg(feature = "log")] crate::log::forward(site, format_args!("{}", msg));
+        #[cfg(feature = "std")] crate::jsonl::write(&crate::jsonl::Report::new("bug", Some(site), msg));
+        #[cfg(feature = "tracing")] crate::tracing::bug(site, msg);
     }
 }
 
+/// Implementation detail of bugsalot's other macros:  constructs a `macro_impl::CallSite` for the current location.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! call_site {
+    ( $expression:expr ) => {
+        $crate::macro_impl::CallSite { strings: concat!(file!(), "\0", module_path!(), "\0", $expression), line: line!(), column: column!() }
+    };
+}
+
+#[test]
+fn call_site_strings() {
+    static CALL_SITE : macro_impl::CallSite = call_site!(stringify!(a + b));
+    assert_eq!(CALL_SITE.file(),        file!());
+    assert_eq!(CALL_SITE.module(),      module_path!());
+    assert_eq!(CALL_SITE.expression(),  "a + b");
+    assert_eq!(CALL_SITE.line,          line!() - 4);
+    assert_eq!(CALL_SITE.column,        47);
+}
+
 /// Reports a bug by logging/breaking.  Unlike `panic!(...)` this is nonfatal and continuable.
 ///
 /// # Examples
@@ -128,37 +446,171 @@ pub mod debugger;
 ///
 /// bug!();
 /// bug!("A simple bug expression, {} allowed");
-/// bug!("A formatting bug expression: {}", "automatically wrapped in format!(...)");
+/// bug!("A formatting bug expression: {}", "automatically wrapped in format_args!(...)");
 /// ```
 #[macro_export]
 macro_rules! bug {
     ( $e:expr ) => {{
-        $crate::macro_impl::log_bug(file!(), line!(), $e);
+        static CALL_SITE : $crate::macro_impl::CallSite = $crate::call_site!("");
+        $crate::macro_impl::log_bug(&CALL_SITE, $e);
         $crate::debugger::break_if_attached();
     }};
     ()              => { $crate::bug!("bug!()") };
-    ( $($tt:tt)+ )  => { $crate::bug!(format!($($tt)+)) };
+    ( $($tt:tt)+ )  => { $crate::bug!(format_args!($($tt)+)) };
 }
 
-/// Log (part of) a line to standard debugging channels.  **Prefer [debugln!]**
+/// Reports reaching supposedly unreachable code by logging/breaking, then evaluates to `fallback`.  Unlike
+/// `unreachable!(...)` this is nonfatal and continuable.
+///
+/// Like [unwrap!], `fallback` can also diverge, e.g. with `return`, `break`, or `continue`.
 ///
-/// Messages should terminate in newlines.  Failure to do so will result in inconsistent behavior between debug message
-/// viewers, as several of them treat each message as a complete package and effectively terminate them with newlines
-/// even if you don't, but a few *don't*, and will instead effectively combine lines.
+/// # Examples
+///
+/// ```no_run
+/// use bugsalot::unreachable_bug;
 ///
-/// For example, in [DebugView] on Windows, `debug!("A"); debug!("B");` will display something like:
+/// fn name(digit: u32) -> &'static str {
+///     match digit {
+///         0 => "zero",
+///         1 => "one",
+///         _ => unreachable_bug!("???", "{} isn't a binary digit", digit),
+///     }
+/// }
 ///
-/// ```text
-/// 33  616.02484131 [12584] A
-/// 34  616.02490234 [12584] B
+/// fn percent(i: u32) -> u32 {
+///     if i > 100 { unreachable_bug!(return 100, "{}% is out of range", i); }
+///     i
+/// }
+///
+/// unreachable_bug!();
 /// ```
 ///
-/// But in [Visual Studio]'s Output tab, or [Visual Studio Code]'s Debug Console, it will instead display something like:
+/// [unwrap!]:  macro.unwrap.html
+#[macro_export]
+macro_rules! unreachable_bug {
+    () => { $crate::unreachable_bug!(()) };
+    ( $fallback:expr $(,)? ) => {{
+        $crate::bug!("entered unreachable code");
+        $fallback
+    }};
+    ( $fallback:expr, $($msg:tt)+ ) => {{
+        $crate::bug!("entered unreachable code: {}", format_args!($($msg)+));
+        $fallback
+    }};
+}
+
+/// Reports reaching unimplemented code by logging/breaking, then evaluates to `fallback`.  Unlike `todo!(...)` this
+/// is nonfatal and continuable.
+///
+/// Like [unwrap!], `fallback` can also diverge, e.g. with `return`, `break`, or `continue`.
+///
+/// # Examples
+///
+/// ```no_run
+/// use bugsalot::todo_bug;
+///
+/// enum Shape { Circle(f32), Polygon(Vec<(f32, f32)>) }
+///
+/// fn area(shape: &Shape) -> f32 {
+///     match shape {
+///         Shape::Circle(r)    => 3.14 * r * r,
+///         Shape::Polygon(_)   => todo_bug!(0.0, "polygon area"),
+///     }
+/// }
+/// ```
+///
+/// [unwrap!]:  macro.unwrap.html
+#[macro_export]
+macro_rules! todo_bug {
+    () => { $crate::todo_bug!(()) };
+    ( $fallback:expr $(,)? ) => {{
+        $crate::bug!("not yet implemented");
+        $fallback
+    }};
+    ( $fallback:expr, $($msg:tt)+ ) => {{
+        $crate::bug!("not yet implemented: {}", format_args!($($msg)+));
+        $fallback
+    }};
+}
+
+#[test]
+fn bug_fallback_examples() {
+    fn name(digit: u32) -> &'static str {
+        match digit {
+            0 => "zero",
+            1 => "one",
+            _ => unreachable_bug!("???", "{} isn't a binary digit", digit),
+        }
+    }
+
+    fn percent(i: u32) -> u32 {
+        if i > 100 { unreachable_bug!(return 100, "{}% is out of range", i); }
+        i
+    }
+
+    assert_eq!((name(0), name(2)), ("zero", "???"));
+    assert_eq!((percent(42), percent(142)), (42, 100));
+    assert_eq!(todo_bug!(42), 42);
+    todo_bug!((), "{} isn't implemented either", "this");
+    unreachable_bug!();
+}
+
+/// Reports a bug at the caller's location by logging/breaking.  Like [bug!], but for `#[track_caller]` functions.
+///
+/// Just like `Option::unwrap`, a helper marked `#[track_caller]` will report the location of *its* caller instead of
+/// its own, which is usually far more useful.
+///
+/// # Examples
+///
+/// ```no_run
+/// use bugsalot::report_bug;
+///
+/// #[track_caller]
+/// fn require_positive(i: i32) -> i32 {
+///     if i <= 0 { report_bug(format_args!("{} isn't positive", i)); } // Reports require_positive's caller
+///     i
+/// }
 ///
-/// ```text
-/// AB
+/// require_positive(-1); // Reported as this line
 /// ```
 ///
+/// [bug!]:     macro.bug.html
+#[track_caller]
+#[inline(always)] // We'd strongly prefer if the debugger showed us the call site, not this function.
+pub fn report_bug(msg: impl core::fmt::Display) {
+    report_bug_at(core::panic::Location::caller(), msg);
+}
+
+/// Reports a bug at `location` by logging/breaking.  Like [bug!], but for an explicit [Location].
+///
+/// [bug!]:     macro.bug.html
+/// [Location]: https://doc.rust-lang.org/core/panic/struct.Location.html
+#[inline(always)] // We'd strongly prefer if the debugger showed us the call site, not this function.
+pub fn report_bug_at(location: &core::panic::Location, msg: impl core::fmt::Display) {
+    macro_impl::log_bug_at(location, msg);
+    debugger::break_if_attached();
+}
+
+#[test]
+fn report_bug_examples() {
+    #[track_caller]
+    fn require_positive(i: i32) -> i32 {
+        if i <= 0 { report_bug(format_args!("{} isn't positive", i)); }
+        i
+    }
+
+    assert_eq!(require_positive(42), 42);
+}
+
+/// Log (part of) a line to standard debugging channels.  **Prefer [debugln!]**
+///
+/// With `std`, output is buffered per thread until a newline (or [output::flush]), and written a line at a time.
+/// Several debug message viewers treat each message as a complete line - e.g. [DebugView] on Windows - while others,
+/// like [Visual Studio]'s Output tab or [Visual Studio Code]'s Debug Console, effectively combine them.  Buffering
+/// means `debug!("A"); debug!("B\n");` displays a single `AB` line in all of them.
+///
+/// Without `std`, output isn't buffered, and messages should terminate in newlines to display consistently.
+///
 /// # Examples
 ///
 /// ```
@@ -172,12 +624,13 @@ macro_rules! bug {
 ///
 /// | platform  | mechanism | notes and caveats |
 /// | --------- | --------- | ----------------- |
-/// | Android   | [__android_log_write](https://developer.android.com/ndk/reference/group/logging#group___logging_1ga32a7173b092ec978b50490bd12ee523b)  | View in [ADB Logcat].
+/// | Android   | [__android_log_write](https://developer.android.com/ndk/reference/group/logging#group___logging_1ga32a7173b092ec978b50490bd12ee523b)  | Long messages are split into ~4 KiB chunks.  View in [ADB Logcat].
 /// | WASM      | [console.log](https://developer.mozilla.org/en-US/docs/Web/API/Console/log)                                                           | View in Developer Tools ([Firefox], [Chrome]).
-/// | Windows   | [OutputDebugStringA](https://docs.microsoft.com/en-us/windows/win32/api/debugapi/nf-debugapi-outputdebugstringa)                      | **Buffer size capped.**  View in [VS]'s Ouput tab, [VSC]'s Debug Console, [DebugView](https://docs.microsoft.com/en-us/sysinternals/downloads/debugview), etc.
+/// | Windows   | [OutputDebugStringA](https://docs.microsoft.com/en-us/windows/win32/api/debugapi/nf-debugapi-outputdebugstringa)                      | Long messages are split into ~4 KiB chunks.  View in [VS]'s Ouput tab, [VSC]'s Debug Console, [DebugView](https://docs.microsoft.com/en-us/sysinternals/downloads/debugview), etc.
 /// | \*nix     | stderr                                                                                                                                | May intermingle with other error reporting, subject to change.
 ///
 /// [debugln!]:             macro.debugln.html
+/// [output::flush]:        output/fn.flush.html
 /// [ADB Logcat]:           https://developer.android.com/studio/command-line/logcat
 /// [Firefox]:              https://developer.mozilla.org/en-US/docs/Tools/Web_Console
 /// [Chrome]:               https://developers.google.com/web/tools/chrome-devtools/console#view
@@ -188,7 +641,10 @@ macro_rules! bug {
 /// [Visual Studio Code]:   https://code.visualstudio.com/
 #[macro_export]
 macro_rules! debug {
-    ( $format:literal $($tt:tt)* )  => { $crate::macro_impl::output(format!($format $($tt)*)) };
+    ( $format:literal $($tt:tt)* )  => {{
+        static CALL_SITE : $crate::macro_impl::CallSite = $crate::call_site!("");
+        $crate::macro_impl::output_debug(&CALL_SITE, format_args!($format $($tt)*))
+    }};
 }
 
 /// Log a line to standard debugging channels.
@@ -207,9 +663,9 @@ macro_rules! debug {
 ///
 /// | platform  | mechanism | notes and caveats |
 /// | --------- | --------- | ----------------- |
-/// | Android   | [__android_log_write](https://developer.android.com/ndk/reference/group/logging#group___logging_1ga32a7173b092ec978b50490bd12ee523b)  | View in [ADB Logcat].
+/// | Android   | [__android_log_write](https://developer.android.com/ndk/reference/group/logging#group___logging_1ga32a7173b092ec978b50490bd12ee523b)  | Long messages are split into ~4 KiB chunks.  View in [ADB Logcat].
 /// | WASM      | [console.log](https://developer.mozilla.org/en-US/docs/Web/API/Console/log)                                                           | View in Developer Tools ([Firefox], [Chrome]).
-/// | Windows   | [OutputDebugStringA](https://docs.microsoft.com/en-us/windows/win32/api/debugapi/nf-debugapi-outputdebugstringa)                      | **Buffer size capped.**  View in [VS]'s Ouput tab, [VSC]'s Debug Console, [DebugView](https://docs.microsoft.com/en-us/sysinternals/downloads/debugview), etc.
+/// | Windows   | [OutputDebugStringA](https://docs.microsoft.com/en-us/windows/win32/api/debugapi/nf-debugapi-outputdebugstringa)                      | Long messages are split into ~4 KiB chunks.  View in [VS]'s Ouput tab, [VSC]'s Debug Console, [DebugView](https://docs.microsoft.com/en-us/sysinternals/downloads/debugview), etc.
 /// | \*nix     | stderr                                                                                                                                | May intermingle with other error reporting, subject to change.
 ///
 /// [debugln!]:             macro.debugln.html
@@ -222,16 +678,20 @@ macro_rules! debug {
 #[macro_export]
 macro_rules! debugln {
     ()                              => { $crate::debugln!("") };
-    ( $format:literal $($tt:tt)* )  => {
-        #[cfg(not(windows))] { $crate::macro_impl::output(format!(concat!($format, "\n\0") $($tt)*))      }
-        #[cfg(    windows )] { $crate::macro_impl::output(format!(concat!($format, "\r\n\0") $($tt)*))    }
-    };
+    ( $format:literal $($tt:tt)* )  => {{
+        static CALL_SITE : $crate::macro_impl::CallSite = $crate::call_site!("");
+        #[cfg(not(windows))] { $crate::macro_impl::output_debug(&CALL_SITE, format_args!(concat!($format, "\n") $($tt)*))      }
+        #[cfg(    windows )] { $crate::macro_impl::output_debug(&CALL_SITE, format_args!(concat!($format, "\r\n") $($tt)*))    }
+    }};
 }
 
 /// Unwraps Options and Results, logging/breaking on errors, but unlike `a.unwrap()` this is nonfatal and continuable.
 ///
 /// Other differences:
 /// * Works on booleans
+/// * Works on raw pointers (fails on null), `Poll` (fails on `Pending`), `ControlFlow` (fails on `Break`),
+///   `std::process::ExitStatus` (fails on non-success), and floats (fails on NaN or infinity)
+/// * If a `Result`'s error implements `Error`, its `Display` and `source()` chain are reported too
 /// * Should breakpoint directly on the line of the unwrap!
 ///
 /// # Examples
@@ -270,7 +730,10 @@ macro_rules! unwrap {
         if $crate::macro_impl::DebugUnwrap::can_unwrap(&unwrap_target) {
             $crate::macro_impl::DebugUnwrap::unwrap_ok(unwrap_target);
         } else {
-            $crate::macro_impl::log_unwrap_failed(file!(), line!(), "unwrap! failed", stringify!($e), unwrap_target);
+            static CALL_SITE : $crate::macro_impl::CallSite = $crate::call_site!(stringify!($e));
+            #[allow(unused_imports)] use $crate::macro_impl::{ErrorProbeResult as _, ErrorProbeOther as _};
+            let as_error = (&$crate::macro_impl::ErrorProbe(&unwrap_target)).as_error_fn();
+            $crate::macro_impl::log_unwrap_failed(&CALL_SITE, "unwrap! failed", unwrap_target, as_error);
             $crate::debugger::break_if_attached();
         }
     }};
@@ -280,7 +743,10 @@ macro_rules! unwrap {
         if $crate::macro_impl::DebugUnwrap::can_unwrap(&unwrap_target) {
             $crate::macro_impl::DebugUnwrap::unwrap_ok(unwrap_target)
         } else {
-            $crate::macro_impl::log_unwrap_failed(file!(), line!(), "unwrap! failed", stringify!($e), unwrap_target);
+            static CALL_SITE : $crate::macro_impl::CallSite = $crate::call_site!(stringify!($e));
+            #[allow(unused_imports)] use $crate::macro_impl::{ErrorProbeResult as _, ErrorProbeOther as _};
+            let as_error = (&$crate::macro_impl::ErrorProbe(&unwrap_target)).as_error_fn();
+            $crate::macro_impl::log_unwrap_failed(&CALL_SITE, "unwrap! failed", unwrap_target, as_error);
             $crate::debugger::break_if_attached();
             $fallback
         }
@@ -291,7 +757,10 @@ macro_rules! unwrap {
         if $crate::macro_impl::DebugUnwrap::can_unwrap(&unwrap_target) {
             $crate::macro_impl::DebugUnwrap::unwrap_ok(unwrap_target);
         } else {
-            $crate::macro_impl::log_unwrap_failed(file!(), line!(), "unwrap! failed", stringify!($e), unwrap_target);
+            static CALL_SITE : $crate::macro_impl::CallSite = $crate::call_site!(stringify!($e));
+            #[allow(unused_imports)] use $crate::macro_impl::{ErrorProbeResult as _, ErrorProbeOther as _};
+            let as_error = (&$crate::macro_impl::ErrorProbe(&unwrap_target)).as_error_fn();
+            $crate::macro_impl::log_unwrap_failed(&CALL_SITE, "unwrap! failed", unwrap_target, as_error);
             $crate::debugger::break_if_attached();
         }
     }};
@@ -299,8 +768,6 @@ macro_rules! unwrap {
 
 #[test] // done this way so we can run tests on android: https://github.com/MaulingMonkey/bugsalot/issues/22
 fn unwrap_examples() {
-    use crate::unwrap;
-
     let a = true;
     let _ : bool = unwrap!(a, false);
     let _ : ()   = unwrap!(a, ());
@@ -327,9 +794,78 @@ fn unwrap_examples() {
 }
 
 #[test]
-fn unwrap_bool_results() {
-    use crate::unwrap;
+fn unwrap_more_types() {
+    use core::ops::ControlFlow;
+    use core::task::Poll;
+
+    let a : Poll<i32> = Poll::Ready(42);
+    let _ : i32 = unwrap!(a, 0);
+    let _ : i32 = unwrap!(Poll::Pending, 0);
 
+    let a : ControlFlow<&'static str, i32> = ControlFlow::Continue(42);
+    let _ : i32 = unwrap!(a, 0);
+    let _ : i32 = unwrap!(ControlFlow::<&'static str, i32>::Break("stop"), 0);
+
+    assert_eq!(unwrap!(1.5f32, 0.0), 1.5);
+    assert_eq!(unwrap!(f32::NAN, 0.0), 0.0);
+    assert_eq!(unwrap!(f64::INFINITY, 0.0), 0.0);
+    assert_eq!(unwrap!(f64::NEG_INFINITY, 0.0), 0.0);
+
+    #[cfg(all(feature = "std", unix))] {
+        let status = std::process::Command::new("false").status();
+        if let Ok(status) = status { let _ : () = unwrap!(status, ()); }
+    }
+}
+
+#[test]
+#[cfg(feature = "std")]
+#[allow(clippy::needless_borrow)] // The borrow selects the autoref specialization
+fn unwrap_error_sources() {
+    use crate::macro_impl::{ErrorProbe, ErrorProbeResult as _, ErrorProbeOther as _};
+    use core::fmt::{self, Display, Formatter};
+    use core::num::ParseIntError;
+
+    #[derive(Debug)] struct ConfigError(ParseIntError);
+    impl Display for ConfigError { fn fmt(&self, f: &mut Formatter) -> fmt::Result { f.write_str("bad config") } }
+    impl std::error::Error for ConfigError { fn source(&self) -> Option<&(dyn std::error::Error + 'static)> { Some(&self.0) } }
+
+    let a : Result<i32, ConfigError> = "x".parse::<i32>().map_err(ConfigError);
+    let b : Result<i32, &str> = Err("reason");
+    assert!((&ErrorProbe(&a)).as_error_fn().is_some());
+    assert!((&ErrorProbe(&b)).as_error_fn::<&str>().is_none());
+    assert!((&ErrorProbe(&Some(42))).as_error_fn::<()>().is_none());
+
+    let output = crate::output::capture(|| assert_eq!(unwrap!(a, 0), 0));
+    assert!(output.ends_with(concat!(
+        "    Found:      Err(ConfigError(ParseIntError { kind: InvalidDigit }))\r\n",
+        "    Error:      bad config\r\n",
+        "    Caused by:  invalid digit found in string\r\n",
+    )), "{:?}", output);
+
+    let output = crate::output::capture(|| assert_eq!(unwrap!(b, 0), 0));
+    assert!(output.ends_with("    Found:      Err(\"reason\")\r\n") && !output.contains("Error:"), "{:?}", output);
+}
+
+#[test]
+#[cfg(feature = "derive")]
+fn unwrap_derived() {
+    #[derive(Debug, PartialEq, DebugUnwrap)]
+    enum LoadResult { #[ok] Loaded(u32), #[ok] Cached, NotFound(&'static str), Corrupt }
+
+    #[derive(Debug, PartialEq, DebugUnwrap)]
+    enum Status<T> { Ready { value: T }, #[err] Failed }
+
+    assert_eq!(unwrap!(LoadResult::Loaded(42), LoadResult::Corrupt), LoadResult::Loaded(42));
+    assert_eq!(unwrap!(LoadResult::Cached,     LoadResult::Corrupt), LoadResult::Cached);
+    assert_eq!(unwrap!(LoadResult::NotFound("a.png"), LoadResult::Cached), LoadResult::Cached);
+    assert_eq!(unwrap!(LoadResult::Corrupt, LoadResult::Cached), LoadResult::Cached);
+    assert_eq!(unwrap!(Status::Ready { value: 1 }, Status::Failed), Status::Ready { value: 1 });
+    assert_eq!(unwrap!(Status::<i32>::Failed, Status::Ready { value: 2 }), Status::Ready { value: 2 });
+}
+
+#[test]
+#[allow(clippy::bool_assert_comparison)] // Clearer as a truth table
+fn unwrap_bool_results() {
     assert_eq!(unwrap!(false, false), false);
     assert_eq!(unwrap!(false, true), true);
     assert_eq!(unwrap!(true, false), true);
@@ -340,6 +876,9 @@ fn unwrap_bool_results() {
 ///
 /// Other differences:
 /// * Works on booleans
+/// * Works on raw pointers (fails on null), `Poll` (fails on `Pending`), `ControlFlow` (fails on `Break`),
+///   `std::process::ExitStatus` (fails on non-success), and floats (fails on NaN or infinity)
+/// * If a `Result`'s error implements `Error`, its `Display` and `source()` chain are reported too
 /// * Should breakpoint directly on the line of the unwrap!
 ///
 /// # Examples
@@ -378,7 +917,10 @@ macro_rules! expect {
         if $crate::macro_impl::DebugUnwrap::can_unwrap(&unwrap_target) {
             $crate::macro_impl::DebugUnwrap::unwrap_ok(unwrap_target);
         } else {
-            $crate::macro_impl::log_unwrap_failed(file!(), line!(), $message, stringify!($e), unwrap_target);
+            static CALL_SITE : $crate::macro_impl::CallSite = $crate::call_site!(stringify!($e));
+            #[allow(unused_imports)] use $crate::macro_impl::{ErrorProbeResult as _, ErrorProbeOther as _};
+            let as_error = (&$crate::macro_impl::ErrorProbe(&unwrap_target)).as_error_fn();
+            $crate::macro_impl::log_unwrap_failed(&CALL_SITE, $message, unwrap_target, as_error);
             $crate::debugger::break_if_attached();
         }
     }};
@@ -388,7 +930,10 @@ macro_rules! expect {
         if $crate::macro_impl::DebugUnwrap::can_unwrap(&unwrap_target) {
             $crate::macro_impl::DebugUnwrap::unwrap_ok(unwrap_target)
         } else {
-            $crate::macro_impl::log_unwrap_failed(file!(), line!(), $message, stringify!($e), unwrap_target);
+            static CALL_SITE : $crate::macro_impl::CallSite = $crate::call_site!(stringify!($e));
+            #[allow(unused_imports)] use $crate::macro_impl::{ErrorProbeResult as _, ErrorProbeOther as _};
+            let as_error = (&$crate::macro_impl::ErrorProbe(&unwrap_target)).as_error_fn();
+            $crate::macro_impl::log_unwrap_failed(&CALL_SITE, $message, unwrap_target, as_error);
             $crate::debugger::break_if_attached();
             $err
         }
@@ -399,7 +944,10 @@ macro_rules! expect {
         if $crate::macro_impl::DebugUnwrap::can_unwrap(&unwrap_target) {
             $crate::macro_impl::DebugUnwrap::unwrap_ok(unwrap_target);
         } else {
-            $crate::macro_impl::log_unwrap_failed(file!(), line!(), $message, stringify!($e), unwrap_target);
+            static CALL_SITE : $crate::macro_impl::CallSite = $crate::call_site!(stringify!($e));
+            #[allow(unused_imports)] use $crate::macro_impl::{ErrorProbeResult as _, ErrorProbeOther as _};
+            let as_error = (&$crate::macro_impl::ErrorProbe(&unwrap_target)).as_error_fn();
+            $crate::macro_impl::log_unwrap_failed(&CALL_SITE, $message, unwrap_target, as_error);
             $crate::debugger::break_if_attached();
         }
     }};
@@ -407,8 +955,6 @@ macro_rules! expect {
 
 #[test] // done this way so we can run tests on android: https://github.com/MaulingMonkey/bugsalot/issues/22
 fn expect_examples() {
-    use crate::expect;
-
     let a = true;
     let _ : bool = expect!(a, "Couldn't do something", false);
     let _ : ()   = expect!(a, "Couldn't do something", ());
@@ -433,3 +979,159 @@ fn expect_examples() {
     let _ : ()  =           expect!(a,  format!("String {}", 42));
     let _ : i32 = unsafe { *expect!(a, &format!("String {}", 42), return) };
 }
+
+/// Checks that a boolean expression is `true`, logging/breaking if it isn't.  Unlike `assert!(...)` this is nonfatal:
+/// it evaluates to the result of the check, so callers can recover.
+///
+/// # Examples
+///
+/// ```no_run
+/// use bugsalot::check;
+///
+/// let items = vec![1, 2, 3];
+/// if !check!(items.len() < 3) { return; }
+/// check!(items.iter().all(|&i| i > 0), "items must be positive: {:?}", items);
+/// ```
+#[macro_export]
+macro_rules! check {
+    ( $cond:expr $(,)? ) => { $crate::check!($cond, "check! failed") };
+    ( $cond:expr, $($msg:tt)+ ) => {{
+        if $cond { true } else {
+            static CALL_SITE : $crate::macro_impl::CallSite = $crate::call_site!(stringify!($cond));
+            $crate::macro_impl::log_check_failed(&CALL_SITE, format_args!($($msg)+));
+            $crate::debugger::break_if_attached();
+            false
+        }
+    }};
+}
+
+/// Checks that two expressions are equal (using `PartialEq`), logging/breaking with both values (using `Debug`) if
+/// they aren't.  Unlike `assert_eq!(...)` this is nonfatal:  it evaluates to the result of the check.
+///
+/// If either value's `{:#?}` spans multiple lines, a line diff of the two is reported instead, colored if writing to a
+/// terminal.
+///
+/// # Examples
+///
+/// ```no_run
+/// use bugsalot::check_eq;
+///
+/// let (a, b) = (1, 2);
+/// if !check_eq!(a, b) { return; }
+/// check_eq!(a + 1, b, "off by one in {}", "example");
+/// ```
+#[macro_export]
+macro_rules! check_eq {
+    ( $left:expr, $right:expr $(,)? ) => { $crate::check_eq!($left, $right, "check_eq! failed") };
+    ( $left:expr, $right:expr, $($msg:tt)+ ) => {
+        match (&$left, &$right) {
+            (left, right) => if *left == *right { true } else {
+                static CALL_SITE : $crate::macro_impl::CallSite = $crate::call_site!(concat!(stringify!($left), " == ", stringify!($right)));
+                $crate::macro_impl::log_check_eq_failed(&CALL_SITE, format_args!($($msg)+), left, right);
+                $crate::debugger::break_if_attached();
+                false
+            }
+        }
+    };
+}
+
+/// Checks that two expressions are not equal (using `PartialEq`), logging/breaking with both values (using `Debug`)
+/// if they are.  Unlike `assert_ne!(...)` this is nonfatal:  it evaluates to the result of the check.
+///
+/// # Examples
+///
+/// ```no_run
+/// use bugsalot::check_ne;
+///
+/// let (a, b) = (1, 1);
+/// if !check_ne!(a, b) { return; }
+/// check_ne!(a, b, "{} and {} should differ", a, b);
+/// ```
+#[macro_export]
+macro_rules! check_ne {
+    ( $left:expr, $right:expr $(,)? ) => { $crate::check_ne!($left, $right, "check_ne! failed") };
+    ( $left:expr, $right:expr, $($msg:tt)+ ) => {
+        match (&$left, &$right) {
+            (left, right) => if *left != *right { true } else {
+                static CALL_SITE : $crate::macro_impl::CallSite = $crate::call_site!(concat!(stringify!($left), " != ", stringify!($right)));
+                $crate::macro_impl::log_check_ne_failed(&CALL_SITE, format_args!($($msg)+), left, right);
+                $crate::debugger::break_if_attached();
+                false
+            }
+        }
+    };
+}
+
+#[test]
+fn check_examples() {
+    let items = [1, 2, 3];
+    assert!( check!(items.len() == 3));
+    assert!(!check!(items.len() < 3));
+    assert!(!check!(items.iter().all(|&i| i > 1), "items must be > 1: {:?}", items));
+
+    assert!( check_eq!(items[0], 1));
+    assert!(!check_eq!(items[0], 2,));
+    assert!(!check_eq!("a", "b", "letters differ: {}", 2));
+
+    assert!( check_ne!(items[0], 2));
+    assert!(!check_ne!(items[0], 1, "{} vs {}", items[0], 1));
+}
+
+#[test]
+fn check_eq_diff() {
+    #[derive(Debug, PartialEq)] struct Config { name: &'static str, width: u32, height: u32, tags: [&'static str; 2] }
+    let left  = Config { name: "window", width: 800, height: 600, tags: ["a", "b"] };
+    let right = Config { name: "window", width: 800, height: 480, tags: ["a", "c"] };
+    assert!(!check_eq!(left, right));
+}
+
+/// Unwraps a `Result`, or reports a bug by logging/breaking, then returns the error from the current function.
+///
+/// Like `?` (including the `From::from` conversion of the error), but reports the failure - with "Expression:",
+/// "Found:", and the error's `source()` chain - at the site of the failure.  To wrap the error in a [Bug] instead,
+/// see [ResultBugExt::bug_err].
+///
+/// # Examples
+///
+/// ```no_run
+/// use bugsalot::try_bug;
+///
+/// fn parse(s: &str) -> Result<i32, std::num::ParseIntError> {
+///     let i = try_bug!(s.parse::<i32>()); // Reports, then returns Err(ParseIntError { ... })
+///     Ok(i)
+/// }
+/// ```
+///
+/// [Bug]:                      struct.Bug.html
+/// [ResultBugExt::bug_err]:    trait.ResultBugExt.html#tymethod.bug_err
+#[macro_export]
+macro_rules! try_bug {
+    ( $e:expr $(,)? ) => {
+        match $e {
+            Ok(value) => value,
+            Err(error) => {
+                static CALL_SITE : $crate::macro_impl::CallSite = $crate::call_site!(stringify!($e));
+                let unwrap_target = Err::<(), _>(&error);
+                #[allow(unused_imports)] use $crate::macro_impl::{ErrorProbeResult as _, ErrorProbeOther as _};
+                let as_error = (&$crate::macro_impl::ErrorProbe(&unwrap_target)).as_error_fn();
+                $crate::macro_impl::log_unwrap_failed(&CALL_SITE, "try_bug! failed", unwrap_target, as_error);
+                $crate::debugger::break_if_attached();
+                return Err(::core::convert::From::from(error));
+            }
+        }
+    };
+}
+
+#[test]
+fn try_bug_examples() {
+    #[derive(Debug, PartialEq)] struct ParseError(core::num::ParseIntError);
+    impl From<core::num::ParseIntError> for ParseError { fn from(e: core::num::ParseIntError) -> Self { Self(e) } }
+
+    fn parse(s: &str) -> Result<i32, ParseError> {
+        let i = try_bug!(s.parse::<i32>());
+        Ok(i)
+    }
+
+    assert_eq!(parse("42"), Ok(42));
+    assert!(parse("x").is_err());
+}
@@ -0,0 +1,125 @@
+//! Integration with the [log](https://docs.rs/log/) crate.  Requires the `log` feature.
+//!
+//! This goes both ways:
+//!
+//! * Reports from [bug!], [unwrap!], and [expect!] are forwarded to the current [log::Log]ger as `Error` records,
+//!   with `file`, `line`, and `module_path` pointing at the macro's call site.
+//! * [Logger] is a [log::Log] implementation that writes records to the same channels [debugln!] does
+//!   (`OutputDebugStringA`, logcat, `console.error`, or stderr.)
+//!
+//! Reports are *not* duplicated if you use both:  [Logger] skips records that bugsalot itself forwarded, as
+//! they've already been written to the same channels.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! # use log0 as log;
+//! bugsalot::log::init().unwrap();
+//!
+//! log::info!("Written to OutputDebugStringA, logcat, console.error, or stderr");
+//! bugsalot::bug!("Written once, even though it's also forwarded to the logger");
+//! ```
+//!
+//! [bug!]:         ../macro.bug.html
+//! [unwrap!]:      ../macro.unwrap.html
+//! [expect!]:      ../macro.expect.html
+//! [debugln!]:     ../macro.debugln.html
+//! [log::Log]:     https://docs.rs/log/0.4/log/trait.Log.html
+
+use crate::ffi::android::Priority;
+use crate::macro_impl::Site;
+use log0 as log;
+use std::cell::Cell;
+use std::fmt;
+
+thread_local! {
+    static FORWARDING : Cell<bool> = const { Cell::new(false) };
+}
+
+/// A [log::Log] implementation that writes to bugsalot's debug channels.
+///
+/// [log::Log]:     https://docs.rs/log/0.4/log/trait.Log.html
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Logger;
+
+impl log::Log for Logger {
+    fn enabled(&self, _metadata: &log::Metadata) -> bool { true }
+
+    fn log(&self, record: &log::Record) {
+        if FORWARDING.with(|f| f.get()) { return; } // Already written by macro_impl::output
+        let priority = match record.level() {
+            log::Level::Error   => Priority::ERROR,
+            log::Level::Warn    => Priority::WARN,
+            log::Level::Info    => Priority::INFO,
+            log::Level::Debug   => Priority::DEBUG,
+            log::Level::Trace   => Priority::VERBOSE,
+        };
+        match (record.file(), record.line()) {
+            (Some(file), Some(line))    => crate::macro_impl::output(priority, format_args!("{}: [{}] {}\r\n", Site { file, line, column: 0, module: None, expression: "" }, record.level(), record.args())),
+            _                           => crate::macro_impl::output(priority, format_args!("{}: [{}] {}\r\n", record.target(), record.level(), record.args())),
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+/// Install [Logger] as the global logger, with a max level of `Trace`.
+pub fn init() -> Result<(), log::SetLoggerError> {
+    init_with_level(log::LevelFilter::Trace)
+}
+
+/// Install [Logger] as the global logger, with the specified max level.
+pub fn init_with_level(level: log::LevelFilter) -> Result<(), log::SetLoggerError> {
+    static LOGGER : Logger = Logger;
+    log::set_logger(&LOGGER)?;
+    log::set_max_level(level);
+    Ok(())
+}
+
+pub(crate) fn forward(site: &Site, message: fmt::Arguments) {
+    if log::Level::Error > log::max_level() { return; }
+    FORWARDING.with(|f| {
+        let prev = f.replace(true);
+        log::logger().log(&log::Record::builder()
+            .level(log::Level::Error)
+            .target(site.module.unwrap_or("bugsalot"))
+            .module_path(site.module)
+            .file(Some(site.file))
+            .line(Some(site.line))
+            .args(message)
+            .build()
+        );
+        f.set(prev);
+    });
+}
+
+#[test]
+fn logger_examples() {
+    use std::{cell::RefCell, string::String, vec::Vec};
+
+    /// Records each record's level, location, and message - and if it was forwarded by bugsalot, and thus skipped by
+    /// [Logger] - before passing it on to [Logger].
+    struct Recorder;
+    thread_local! { static RECORDS : RefCell<Vec<String>> = const { RefCell::new(Vec::new()) }; }
+    impl log::Log for Recorder {
+        fn enabled(&self, _metadata: &log::Metadata) -> bool { true }
+        fn log(&self, record: &log::Record) {
+            let forwarded = if FORWARDING.with(|f| f.get()) { " (forwarded)" } else { "" };
+            let record_string = format!("{} {}:{} {} {}{}", record.level(), record.file().unwrap_or("?"), record.line().unwrap_or(0), record.module_path().unwrap_or("?"), record.args(), forwarded);
+            RECORDS.with(|records| records.borrow_mut().push(record_string));
+            Logger.log(record);
+        }
+        fn flush(&self) {}
+    }
+
+    static RECORDER : Recorder = Recorder;
+    log::set_logger(&RECORDER).unwrap();
+    log::set_max_level(log::LevelFilter::Trace);
+
+    log::info!("logger_examples: info");            let info_line = line!();
+    crate::bug!("logger_examples: forwarded bug");  let bug_line = line!();
+    RECORDS.with(|records| assert_eq!(*records.borrow(), [
+        format!("INFO {}:{} {} logger_examples: info", file!(), info_line, module_path!()),
+        format!("ERROR {}:{} {} logger_examples: forwarded bug (forwarded)", file!(), bug_line, module_path!()),
+    ]));
+}
@@ -0,0 +1,514 @@
+//! Control where bugsalot's reports and [debug!] output are written.
+//!
+//! By default, output is written to the platform's standard debugging channels:
+//!
+//! | platform  | mechanism |
+//! | --------- | --------- |
+//! | Android   | `__android_log_write`
+//! | WASM      | `console.error` (requires the `wasm-bindgen` or `stdweb` feature)
+//! | Windows   | `OutputDebugStringA`
+//! | \*nix     | stderr
+//!
+//! On \*nix, stderr gets `\n` line endings.  When it's a terminal, reports are colored:  the site is bold, the
+//! expression dimmed, and the value found red.  Set [NO_COLOR](https://no-color.org/) to disable color, or
+//! [CLICOLOR_FORCE](https://bixense.com/clicolors/) to enable it even when stderr isn't a terminal.
+//!
+//! Targets without any of the above - e.g. embedded or kernel-style `no_std` targets - should [set_callback] to
+//! receive output, which is otherwise discarded.
+//!
+//! Output can also be written to a log file, with [file::RotatingFile](../file/struct.RotatingFile.html).
+//!
+//! Each report is formatted in full before being written, then written to every sink with one call per sink, while
+//! holding a lock shared with other threads' reports (with `std`).  Reports from concurrent threads won't interleave,
+//! and a report made while formatting another - e.g. by a `Debug` impl that itself calls [bug!] - won't deadlock.
+//!
+//! `OutputDebugStringA` and logcat truncate messages past ~4 KiB, so longer messages are split into numbered chunks,
+//! like `(1/3) `, with one call per chunk.  The chunks are still written while holding the lock.
+//!
+//! [debug!]:   ../macro.debug.html
+//! [bug!]:     ../macro.bug.html
+
+use crate::buffer::Buffer;
+use alloc::boxed::Box;
+use crate::ffi::android::Priority;
+use crate::header::LineHeaders;
+use core::fmt;
+use core::sync::atomic::{AtomicPtr, AtomicU8, Ordering};
+
+/// A function that receives bugsalot's output, one report or [debug!] message per call.
+///
+/// [debug!]:   ../macro.debug.html
+pub type Callback = fn(fmt::Arguments);
+
+static CALLBACK : AtomicPtr<()> = AtomicPtr::new(core::ptr::null_mut());
+static ANDROID_TAG : AtomicPtr<&'static str> = AtomicPtr::new(core::ptr::null_mut());
+
+/// Send all output to `callback` instead of the platform's standard debugging channels, or restore the default with `None`.
+///
+/// # Examples
+///
+/// ```
+/// fn write_to_uart(args: core::fmt::Arguments) {
+///     // ...
+/// #   let _ = args;
+/// }
+///
+/// bugsalot::output::set_callback(Some(write_to_uart));
+/// bugsalot::debugln!("Written to the UART");
+/// bugsalot::output::set_callback(None);
+/// ```
+pub fn set_callback(callback: Option<Callback>) {
+    let callback = match callback {
+        Some(callback)  => callback as *mut (),
+        None            => core::ptr::null_mut(),
+    };
+    CALLBACK.store(callback, Ordering::Release);
+}
+
+/// Write to logcat with `tag` - e.g. your app's name - or restore the default tag, `"bugsalot"`, with `None`.
+///
+/// Output is written at a [Priority] that depends on the macro:  `DEBUG` for [debug!] and [debugln!], `ERROR` for
+/// bug reports, and `FATAL` for panics.  The tag is only used on Android.
+///
+/// This is intended to be called once, at startup:  previous tags are leaked, as other threads might still be using
+/// them.
+///
+/// # Examples
+///
+/// ```
+/// bugsalot::output::set_android_tag(Some("MyGame"));
+/// bugsalot::debugln!("Visible via `adb logcat MyGame:D *:S`");
+/// bugsalot::output::set_android_tag(None);
+/// ```
+///
+/// [debug!]:   ../macro.debug.html
+/// [debugln!]: ../macro.debugln.html
+pub fn set_android_tag(tag: Option<&'static str>) {
+    let tag = match tag {
+        Some(tag)   => Box::into_raw(Box::new(tag)),
+        None        => core::ptr::null_mut(),
+    };
+    ANDROID_TAG.store(tag, Ordering::Release);
+}
+
+#[allow(dead_code)] // Only used on Android
+fn android_tag() -> &'static str {
+    let tag = ANDROID_TAG.load(Ordering::Acquire);
+    // SAFETY: only ever set from a leaked `Box<&'static str>` by `set_android_tag`
+    if tag.is_null() { "bugsalot" } else { unsafe { *tag } }
+}
+
+/// Held while writing a report (or [debug!] message) to every sink, so reports from concurrent threads don't
+/// interleave.  Reentrant, so a report made while formatting another (e.g. by a `Debug` impl) doesn't deadlock.
+///
+/// Without `std`, there are no threads to lock out (or no way to tell them apart), so this does nothing.
+///
+/// [debug!]:   ../macro.debug.html
+pub(crate) struct ReportLock {
+    #[cfg(feature = "std")] guard: Option<std::sync::MutexGuard<'static, ()>>,
+}
+
+#[cfg(feature = "std")] std::thread_local! { static HELD : core::cell::Cell<bool> = const { core::cell::Cell::new(false) }; }
+
+impl ReportLock {
+    pub fn lock() -> Self {
+        #[cfg(feature = "std")] {
+            static LOCK : std::sync::Mutex<()> = std::sync::Mutex::new(());
+            // During thread teardown `HELD` may be gone, in which case we write unlocked rather than not at all.
+            if HELD.try_with(|held| held.replace(true)).unwrap_or(true) { return Self { guard: None }; }
+            Self { guard: Some(LOCK.lock().unwrap_or_else(|poisoned| poisoned.into_inner())) }
+        }
+        #[cfg(not(feature = "std"))] { Self {} }
+    }
+}
+
+impl Drop for ReportLock {
+    fn drop(&mut self) {
+        #[cfg(feature = "std")] if self.guard.is_some() { let _ = HELD.try_with(|held| held.set(false)); }
+    }
+}
+
+pub(crate) fn write(priority: Priority, args: fmt::Arguments) {
+    let _lock = ReportLock::lock();
+    let message = Buffer::format(args);
+    #[cfg(feature = "std")] crate::file::write(&Text { text: message.as_str(), ansi: false });
+
+    let callback = CALLBACK.load(Ordering::Acquire);
+    if !callback.is_null() {
+        // SAFETY: only ever set from a valid `Callback` by `set_callback`
+        let callback = unsafe { core::mem::transmute::<*mut (), Callback>(callback) };
+        return callback(format_args!("{}", message.as_str()));
+    }
+
+    write_platform(priority, message);
+}
+
+/// Write any partial line of [debug!] output buffered by the current thread, ending it with a newline.
+///
+/// With `std`, [debug!] output is buffered per thread until a newline, so every viewer and sink sees the same whole
+/// lines - `debug!("A"); debug!("B\n");` writes a single `AB` line everywhere.  Partial lines are also flushed before
+/// the thread reports a bug, and when the thread exits (although some platforms skip this for the main thread).
+/// Without `std`, [debug!] output is never buffered, and this does nothing.
+///
+/// # Examples
+///
+/// ```
+/// use bugsalot::debug;
+///
+/// debug!("Working...");
+/// bugsalot::output::flush(); // Write "Working...\n" now
+/// debug!("Done\n");
+/// ```
+///
+/// [debug!]:   ../macro.debug.html
+pub fn flush() {
+    #[cfg(feature = "std")] {
+        let line = PENDING.try_with(|pending| pending.borrow_mut().take_line()).ok().flatten();
+        if let Some(line) = line { write_debug_lines(format_args!("{}", line.as_str())); }
+    }
+}
+
+/// The platform's line ending, for terminating partial lines of [debug!] output.
+///
+/// [debug!]:   ../macro.debug.html
+#[cfg(feature = "std")] pub(crate) const EOL : &str = if cfg!(windows) { "\r\n" } else { "\n" };
+
+/// Write [debug!] output, starting lines with `header` and (with `std`) buffering any trailing partial line.
+///
+/// [debug!]:   ../macro.debug.html
+pub(crate) fn write_debug(header: &dyn fmt::Display, args: fmt::Arguments) {
+    #[cfg(feature = "std")] {
+        // Formatted outside the `RefCell` borrow, in case a Display impl writes debug! messages itself.
+        let start = PENDING.try_with(|pending| pending.borrow().is_empty()).unwrap_or(true);
+        let text = Buffer::format(format_args!("{}", LineHeaders { header, args, start }));
+        let text = text.as_str();
+        if start && text.ends_with('\n') { return write_debug_lines(format_args!("{}", text)); } // Nothing to buffer
+
+        match PENDING.try_with(|pending| pending.borrow_mut().push(text)) {
+            Ok(Some(lines)) => write_debug_lines(format_args!("{}", lines.as_str())),
+            Ok(None)        => {},
+            Err(_)          => write_debug_lines(format_args!("{}", text)), // Unbuffered during thread teardown
+        }
+    }
+    #[cfg(not(feature = "std"))] write_debug_lines(format_args!("{}", LineHeaders { header, args, start: true }));
+}
+
+fn write_debug_lines(args: fmt::Arguments) {
+    #[cfg(feature = "std")] if crate::recorder::record(args) { return; }
+    write(Priority::DEBUG, args);
+}
+
+/// The current thread's partial line of [debug!] output, in a fixed-size buffer so buffering never allocates.
+///
+/// [debug!]:   ../macro.debug.html
+#[cfg(feature = "std")] struct PendingLine { buffer: [u8; PendingLine::CAPACITY], len: usize }
+
+#[cfg(feature = "std")] std::thread_local! { static PENDING : core::cell::RefCell<PendingLine> = const { core::cell::RefCell::new(PendingLine::new()) }; }
+
+#[cfg(feature = "std")] impl PendingLine {
+    /// Partial lines longer than this are written as lines of their own, rather than buffered.
+    const CAPACITY : usize = 1024;
+
+    const fn new() -> Self { Self { buffer: [0; Self::CAPACITY], len: 0 } }
+
+    fn is_empty(&self) -> bool { self.len == 0 }
+
+    fn as_str(&self) -> &str {
+        // Only whole `&str`s are ever appended.
+        core::str::from_utf8(&self.buffer[..self.len]).unwrap_or("")
+    }
+
+    /// Append `text`, returning any complete lines - or the whole partial line, newline terminated, if it would grow
+    /// past [CAPACITY](Self::CAPACITY).
+    fn push(&mut self, text: &str) -> Option<Buffer> {
+        let (lines, rest) = text.split_at(text.rfind('\n').map_or(0, |newline| newline + 1));
+        let mut out = None;
+        if !lines.is_empty() {
+            out = Some(Buffer::format(format_args!("{}{}", self.as_str(), lines)));
+            self.len = 0;
+        }
+        if self.len + rest.len() > Self::CAPACITY {
+            let mut out = out.unwrap_or_else(Buffer::new);
+            let _ = fmt::Write::write_fmt(&mut out, format_args!("{}{}{}", self.as_str(), rest, EOL));
+            self.len = 0;
+            return Some(out);
+        }
+        self.buffer[self.len .. self.len + rest.len()].copy_from_slice(rest.as_bytes());
+        self.len += rest.len();
+        out
+    }
+
+    /// Take the partial line, if any, newline terminated.
+    fn take_line(&mut self) -> Option<Buffer> {
+        if self.is_empty() { return None; }
+        let line = Buffer::format(format_args!("{}{}", self.as_str(), EOL));
+        self.len = 0;
+        Some(line)
+    }
+}
+
+#[cfg(feature = "std")] impl Drop for PendingLine {
+    fn drop(&mut self) {
+        if let Some(line) = self.take_line() { write_debug_lines(format_args!("{}", line.as_str())); }
+    }
+}
+
+/// Whether stderr gets color:  `COLOR_UNKNOWN` until first decided by [is_color_stderr].
+static STDERR_COLOR : AtomicU8 = AtomicU8::new(COLOR_UNKNOWN);
+
+const COLOR_UNKNOWN : u8 = 0;
+const COLOR_NO      : u8 = 1;
+const COLOR_YES     : u8 = 2;
+
+/// `true` if output is going to a terminal via stderr, which (probably) understands ANSI escape codes.
+///
+/// Follows the [NO_COLOR](https://no-color.org/) and [CLICOLOR_FORCE](https://bixense.com/clicolors/) conventions,
+/// so `NO_COLOR=1` disables color, and `CLICOLOR_FORCE=1` enables it even when stderr isn't a terminal.  Decided
+/// once, on first use, so reports don't check the environment each time.
+pub(crate) fn is_color_stderr() -> bool {
+    if !CALLBACK.load(Ordering::Acquire).is_null() { return false; }
+
+    match STDERR_COLOR.load(Ordering::Relaxed) {
+        COLOR_UNKNOWN => {
+            let color = decide_color_stderr();
+            STDERR_COLOR.store(if color { COLOR_YES } else { COLOR_NO }, Ordering::Relaxed);
+            color
+        },
+        color => color == COLOR_YES,
+    }
+}
+
+fn decide_color_stderr() -> bool {
+    #[cfg(all(unix, not(target_os = "android")))] {
+        // `getenv` instead of `std::env::var_os`, which allocates:  reports must work even if the allocator doesn't.
+        let var = |name: &[u8]| {
+            let value = unsafe { crate::ffi::unix::getenv(name.as_ptr()) };
+            if value.is_null() { None } else { Some(unsafe { core::ffi::CStr::from_ptr(value.cast()) }.to_bytes()) }
+        };
+        if var(b"NO_COLOR\0").is_some_and(|v| !v.is_empty()) { return false; }
+        if var(b"CLICOLOR_FORCE\0").is_some_and(|v| !v.is_empty() && v != b"0") { return true; }
+        var(b"TERM\0") != Some(b"dumb") && unsafe { crate::ffi::unix::isatty(2) } != 0
+    }
+    #[cfg(not(all(unix, not(target_os = "android"))))] { false }
+}
+
+/// ANSI escapes used to highlight parts of reports written to terminals.
+pub(crate) const BOLD   : &str = "\x1B[1m";
+pub(crate) const DIM    : &str = "\x1B[2m";
+pub(crate) const RED    : &str = "\x1B[31m";
+pub(crate) const GREEN  : &str = "\x1B[32m";
+pub(crate) const RESET  : &str = "\x1B[0m";
+
+/// `value`, wrapped in the ANSI escape `sgr` (and a reset) if `color`.
+pub(crate) struct Ansi<T> { pub color: bool, pub sgr: &'static str, pub value: T }
+
+impl<T: fmt::Display> fmt::Display for Ansi<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.color { write!(f, "{}{}{}", self.sgr, self.value, RESET) } else { fmt::Display::fmt(&self.value, f) }
+    }
+}
+
+/// Output `text` adjusted for a sink:  with `\r\n` line endings reduced to `\n` (except on Windows), and with any ANSI
+/// escape sequences removed, unless `ansi`.
+#[allow(dead_code)] // Only used with `std`, or on unix
+struct Text<'a> { text: &'a str, ansi: bool }
+
+impl<'a> fmt::Display for Text<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut rest = self.text;
+        while let Some(i) = rest.find(|c| c == '\r' || (c == '\x1B' && !self.ansi)) {
+            f.write_str(&rest[..i])?;
+            rest = &rest[i..];
+            if rest.starts_with('\x1B') {
+                rest = &rest[escape_len(rest)..];
+            } else if rest.starts_with("\r\n") && !cfg!(windows) {
+                rest = &rest[1..];
+                f.write_str("\n")?;
+                rest = &rest[1..];
+            } else {
+                f.write_str("\r")?;
+                rest = &rest[1..];
+            }
+        }
+        f.write_str(rest)
+    }
+}
+
+/// The length of the ANSI escape sequence at the start of `s`:  a CSI sequence like `\x1B[31m`, an OSC sequence like
+/// an OSC 8 hyperlink (terminated by BEL or `\x1B\\`), or else just the `\x1B`.
+#[allow(dead_code)] // Only used with `std`, or on unix
+fn escape_len(s: &str) -> usize {
+    let bytes = s.as_bytes();
+    match bytes.get(1) {
+        Some(b'[') => bytes[2..].iter().position(|b| (0x40..=0x7E).contains(b)).map_or(s.len(), |end| 2 + end + 1),
+        Some(b']') => match s[2..].find(['\x07', '\x1B']) {
+            Some(end) if s[2+end..].starts_with("\x1B\\") => 2 + end + 2,
+            Some(end) => 2 + end + 1,
+            None => s.len(),
+        },
+        _ => 1,
+    }
+}
+
+/// Text bytes per `OutputDebugStringA` call, which DebugView and friends receive via a 4 KiB `DBWIN_BUFFER`, minus
+/// the writer's pid and the `\0` terminator.
+#[cfg(windows)] const WIN32_LIMIT : usize = 4096 - 4 - 1;
+
+/// Text bytes per logcat entry with `tag`, which shares `LOGGER_ENTRY_MAX_PAYLOAD` (4068 bytes) with the priority
+/// byte, the tag, and both of their `\0` terminators.
+#[cfg(target_os = "android")] fn android_limit(tag: &str) -> usize { 4068 - 1 - (tag.len() + 1) - 1 }
+
+/// Write `message` in chunks of at most `limit` bytes, for channels that would otherwise truncate it.
+///
+/// Chunks are cut after the last newline that fits, or at the last UTF-8 character boundary if no newline does.  When
+/// there's more than one chunk, each is numbered like `(2/3) `, within the limit, so the pieces can be put back together.
+/// (Limits too small for a label and a 4 byte character are exceeded, rather than looping forever.)
+#[allow(dead_code)] // Only used on some platforms
+fn write_chunked(message: &str, limit: usize, mut write: impl FnMut(Buffer)) {
+    if message.len() <= limit { return write(Buffer::format(format_args!("{}", message))); }
+
+    let mut digits = 1u32;
+    let chunks = loop {
+        let label = 2 * digits as usize + 4; // "(" + i + "/" + n + ") "
+        let chunks = split(message, limit.saturating_sub(label).max(4)); // Always room for at least one char
+        if chunks.len() < 10usize.pow(digits) { break chunks; }
+        digits += 1;
+    };
+
+    let n = chunks.len();
+    for (i, chunk) in chunks.into_iter().enumerate() { write(Buffer::format(format_args!("({}/{}) {}", i+1, n, chunk))); }
+}
+
+/// Split `message` into pieces of at most `max` bytes (`max >= 4`), preferring to cut after newlines.
+fn split(message: &str, max: usize) -> alloc::vec::Vec<&str> {
+    let mut chunks = alloc::vec::Vec::new();
+    let mut rest = message;
+    while rest.len() > max {
+        let mut end = max;
+        while !rest.is_char_boundary(end) { end -= 1; }
+        let end = rest[..end].rfind('\n').map_or(end, |newline| newline + 1);
+        chunks.push(&rest[..end]);
+        rest = &rest[end..];
+    }
+    if !rest.is_empty() { chunks.push(rest); }
+    chunks
+}
+
+fn write_platform(#[allow(unused_variables)] priority: Priority, message: Buffer) {
+    #[allow(unused_imports)] use crate::ffi::*;
+
+    #[cfg(windows)] write_chunked(message.as_str(), WIN32_LIMIT, |mut chunk| {
+        chunk.require_nul();
+        unsafe { win32::OutputDebugStringA(chunk.as_bytes().as_ptr()) };
+    });
+    #[cfg(target_os = "android")] {
+        let tag = android_tag();
+        let mut tag_nul = Buffer::format(format_args!("{}", tag));
+        tag_nul.require_nul();
+        write_chunked(message.as_str(), android_limit(tag), |mut chunk| {
+            chunk.require_nul();
+            unsafe { android::__android_log_write(priority, tag_nul.as_bytes().as_ptr(), chunk.as_bytes().as_ptr()) };
+        });
+    }
+
+    #[cfg(target_arch = "wasm32")] wasm::console::error(message.as_str());
+    #[cfg(all(unix, not(target_os = "android")))] let message = Buffer::format(format_args!("{}", Text { text: message.as_str(), ansi: true }));
+    #[cfg(all(unix, not(target_os = "android"), feature = "std"))] std::eprint!("{}", message.as_str());
+    #[cfg(all(unix, not(target_os = "android"), not(feature = "std")))] {
+        let mut remaining = message.as_str().as_bytes();
+        while !remaining.is_empty() {
+            let written = unsafe { unix::write(2, remaining.as_ptr(), remaining.len()) };
+            if written <= 0 { break; }
+            remaining = &remaining[written as usize..];
+        }
+    }
+    #[cfg(not(any(windows, unix, target_arch = "wasm32")))] let _ = message;
+}
+
+/// Run `f`, returning the output it wrote on the current thread.  Serialized with other captures, as the callback
+/// this installs is global.
+#[cfg(all(test, feature = "std"))] pub(crate) fn capture(f: impl FnOnce()) -> alloc::string::String {
+    use std::{cell::RefCell, string::String, sync::Mutex};
+    static LOCK : Mutex<()> = Mutex::new(());
+    std::thread_local! { static CAPTURED : RefCell<String> = const { RefCell::new(String::new()) }; }
+    fn callback(args: fmt::Arguments) { CAPTURED.with(|captured| { let _ = fmt::Write::write_fmt(&mut *captured.borrow_mut(), args); }); }
+
+    let _lock = LOCK.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    set_callback(Some(callback));
+    f();
+    set_callback(None);
+    CAPTURED.with(|captured| core::mem::take(&mut *captured.borrow_mut()))
+}
+
+#[test]
+fn text_for_sinks() {
+    use alloc::string::ToString;
+    let report = "\x1B]8;;file:///src/main.rs\x1B\\\x1B[1msrc/main.rs:1:2\x1B[0m\x1B]8;;\x1B\\: bug\r\n    Found:      \x1B[31mNone\x1B[0m\r\n";
+    let eol = if cfg!(windows) { "\r\n" } else { "\n" };
+    assert_eq!(Text { text: report, ansi: false }.to_string(), ["src/main.rs:1:2: bug", eol, "    Found:      None", eol].concat());
+    assert_eq!(Text { text: report, ansi: true }.to_string(), report.replace("\r\n", eol));
+    assert_eq!(Text { text: "a\rb\x1B", ansi: false }.to_string(), "a\rb");
+}
+
+#[test]
+fn chunked_output() {
+    use alloc::{string::String, vec::Vec};
+    let collect = |message: &str, limit: usize| {
+        let mut chunks = Vec::new();
+        write_chunked(message, limit, |chunk| chunks.push(String::from(chunk.as_str())));
+        chunks
+    };
+
+    assert_eq!(collect("short\r\n", 16), ["short\r\n"]);
+    assert_eq!(collect("first line\r\nsecond line\r\n", 20), ["(1/2) first line\r\n", "(2/2) second line\r\n"]);
+    assert_eq!(collect("0123456789abcdef", 10), ["(1/4) 0123", "(2/4) 4567", "(3/4) 89ab", "(4/4) cdef"]);
+    assert_eq!(collect("ααααααα", 11), ["(1/4) αα", "(2/4) αα", "(3/4) αα", "(4/4) α"]); // 5 byte budget, 2 byte chars
+
+    let long = "x".repeat(100);
+    let chunks = collect(&long, 12);
+    assert_eq!(chunks.len(), 25);
+    assert!(chunks.iter().all(|chunk| chunk.len() <= 12));
+    assert_eq!(chunks[9], "(10/25) xxxx");
+    assert_eq!(chunks.iter().map(|chunk| &chunk[chunk.find(' ').unwrap()+1..]).collect::<String>(), long);
+}
+
+#[cfg(feature = "std")] #[test]
+fn pending_lines() {
+    use alloc::string::ToString;
+    let mut pending = PendingLine::new();
+    let mut push = |text: &str| pending.push(text).map(|lines| lines.as_str().to_string());
+    assert_eq!(push("A"),           None);
+    assert_eq!(push("B\nC"),        Some("AB\n".into()));
+    assert_eq!(push("D\nE\nF"),     Some("CD\nE\n".into()));
+
+    let long = "x".repeat(PendingLine::CAPACITY);
+    assert_eq!(push(&long),         Some(["F", &long, EOL].concat()));
+    assert_eq!(pending.take_line().map(|line| line.as_str().to_string()), None);
+
+    let output = capture(|| {
+        crate::debug!("partial");
+        crate::bug!("pending_lines: bug");
+        crate::debug!("flushed");
+        flush();
+    });
+    let lines = output.lines().collect::<alloc::vec::Vec<_>>(); // Reports end lines with \r\n regardless
+    assert_eq!(lines[0], "partial", "{:?}", output);
+    assert!(lines[1].ends_with(": pending_lines: bug"), "{:?}", output);
+    assert!(output.ends_with(["flushed", EOL].concat().as_str()), "{:?}", output);
+}
+
+#[test]
+fn report_lock_reentrancy() {
+    struct Reentrant;
+    impl fmt::Debug for Reentrant {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            crate::bug!("report_lock_reentrancy: reported while formatting another report");
+            f.write_str("Reentrant")
+        }
+    }
+
+    let threads = (0..4).map(|_| std::thread::spawn(|| {
+        for _ in 0..10 { crate::unwrap!(Err::<(), _>(Reentrant)); }
+    })).collect::<alloc::vec::Vec<_>>();
+    for thread in threads { thread.join().unwrap(); }
+}
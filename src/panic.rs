@@ -0,0 +1,63 @@
+use crate::header::Header;
+use crate::macro_impl::Site;
+use std::panic::{self, PanicHookInfo};
+
+/// Replace the current panic hook with one that reports panics through bugsalot's debug channels, then breaks.
+///
+/// Panic messages use the same [header] as [bug!] - `file:line:column: message` by default, or
+/// `file(line,column): message` on Windows - and are written to the same channels as [debugln!].  This makes them
+/// visible in logcat, `console.error`, and Windows debug output, where the default stderr message would be lost.  If a
+/// backtrace is enabled via `RUST_BACKTRACE`, it's included in the report.
+///
+/// If a debugger is attached, the hook breaks *before* unwinding starts, so the callstack still includes the
+/// frame that panicked.
+///
+/// This replaces, rather than chains to, any previously installed panic hook.
+///
+/// # Examples
+///
+/// ```no_run
+/// bugsalot::install_panic_hook();
+///
+/// panic!("Written to OutputDebugStringA, logcat, console.error, or stderr");
+/// ```
+///
+/// [header]:       header/index.html
+/// [bug!]:         macro.bug.html
+/// [debugln!]:     macro.debugln.html
+pub fn install_panic_hook() {
+    panic::set_hook(Box::new(panic_hook));
+}
+
+fn panic_hook(info: &PanicHookInfo) {
+    let msg = match info.payload().downcast_ref::<&str>() {
+        Some(msg) => *msg,
+        None => match info.payload().downcast_ref::<String>() {
+            Some(msg) => msg.as_str(),
+            None => "Box<dyn Any>",
+        },
+    };
+
+    let thread = std::thread::current();
+    let thread = thread.name().unwrap_or("<unnamed>");
+
+    let backtrace = std::backtrace::Backtrace::capture();
+    let backtrace = match backtrace.status() {
+        std::backtrace::BacktraceStatus::Captured => format!("stack backtrace:\r\n{}\r\n", backtrace),
+        _ => String::new(),
+    };
+
+    let site = info.location().map(|location| Site::from_location(location, ""));
+    let header = match site.as_ref() {
+        Some(site)  => Header::error(site, crate::output::is_color_stderr()),
+        None        => Header::error_without_site(),
+    };
+    let lock = crate::output::ReportLock::lock();
+    crate::output::flush();
+    crate::recorder::dump();
+    crate::macro_impl::output(crate::ffi::android::Priority::FATAL, format_args!("{}thread '{}' panicked: {}\r\n{}", header, thread, msg, backtrace));
+    crate::jsonl::write(&crate::jsonl::Report::new("panic", site.as_ref(), &msg));
+    drop(lock);
+
+    crate::debugger::break_if_attached();
+}
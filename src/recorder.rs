@@ -0,0 +1,145 @@
+//! A flight recorder for [debug!] output:  keep recent messages in memory, and only write them when a bug is reported.
+//! Requires `std`.
+//!
+//! Once a [FlightRecorder] is installed, [debug!] and [debugln!] messages are kept in an in-memory ring buffer
+//! instead of being written.  When [bug!], [unwrap!], [expect!], [check!], etc. report a bug - or the [panic hook]
+//! reports a panic - the buffered messages are written first, so the report comes with what led up to it.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use bugsalot::recorder::FlightRecorder;
+//!
+//! FlightRecorder::new().max_messages(100).install();
+//!
+//! for i in 0..1000 { bugsalot::debugln!("step {}", i); }  // Buffered, not written
+//! bugsalot::bug!("Writes steps 900 ..= 999, then this");
+//! ```
+//!
+//! [debug!]:       ../macro.debug.html
+//! [debugln!]:     ../macro.debugln.html
+//! [bug!]:         ../macro.bug.html
+//! [unwrap!]:      ../macro.unwrap.html
+//! [expect!]:      ../macro.expect.html
+//! [check!]:       ../macro.check.html
+//! [panic hook]:   ../fn.install_panic_hook.html
+
+use crate::ffi::android::Priority;
+use std::collections::VecDeque;
+use std::fmt;
+use std::sync::{Mutex, MutexGuard};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static RECORDER : Mutex<Option<Ring>> = Mutex::new(None);
+
+/// Whether `RECORDER` is (probably) `Some`, so [debug!] can skip the lock when there's no recorder installed.
+///
+/// [debug!]:   ../macro.debug.html
+static INSTALLED : AtomicBool = AtomicBool::new(false);
+
+/// Configuration for the [debug!] flight recorder.
+///
+/// [debug!]:   ../macro.debug.html
+#[derive(Clone, Copy, Debug)]
+pub struct FlightRecorder {
+    max_messages:   usize,
+    max_bytes:      usize,
+}
+
+impl Default for FlightRecorder {
+    fn default() -> Self { Self::new() }
+}
+
+impl FlightRecorder {
+    /// Keep the last 1000 messages, up to 64 KiB.
+    pub fn new() -> Self { Self { max_messages: 1000, max_bytes: 64 << 10 } }
+
+    /// Keep at most `max_messages` messages.
+    pub fn max_messages(mut self, max_messages: usize) -> Self { self.max_messages = max_messages; self }
+
+    /// Keep at most `max_bytes` bytes of messages.
+    pub fn max_bytes(mut self, max_bytes: usize) -> Self { self.max_bytes = max_bytes; self }
+
+    /// Start recording [debug!] output, replacing (and discarding the messages of) any previously installed recorder.
+    ///
+    /// [debug!]:   ../macro.debug.html
+    pub fn install(self) {
+        let mut recorder = lock();
+        *recorder = Some(Ring { config: self, messages: VecDeque::new(), bytes: 0 });
+        INSTALLED.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Stop recording [debug!] output, discarding any recorded messages.
+///
+/// [debug!]:   ../macro.debug.html
+pub fn uninstall() {
+    let mut recorder = lock();
+    *recorder = None;
+    INSTALLED.store(false, Ordering::Relaxed);
+}
+
+struct Ring {
+    config:     FlightRecorder,
+    messages:   VecDeque<String>,
+    bytes:      usize,
+}
+
+impl Ring {
+    fn push(&mut self, message: String) {
+        self.bytes += message.len();
+        self.messages.push_back(message);
+        while self.messages.len() > self.config.max_messages || self.bytes > self.config.max_bytes {
+            match self.messages.pop_front() {
+                Some(evicted)   => self.bytes -= evicted.len(),
+                None            => break,
+            }
+        }
+    }
+
+    fn take(&mut self) -> VecDeque<String> {
+        self.bytes = 0;
+        std::mem::take(&mut self.messages)
+    }
+}
+
+fn lock() -> MutexGuard<'static, Option<Ring>> {
+    RECORDER.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Record a [debug!] message.  Returns `false` if there's no recorder installed, and the message should be written.
+///
+/// [debug!]:   ../macro.debug.html
+pub(crate) fn record(args: fmt::Arguments) -> bool {
+    if !INSTALLED.load(Ordering::Relaxed) || lock().is_none() { return false; }
+    let message = fmt::format(args); // Outside the lock, in case a Display impl writes debug! messages itself
+    if let Some(ring) = lock().as_mut() { ring.push(message); }
+    true
+}
+
+/// Write (and forget) all recorded messages, ahead of a report.
+pub(crate) fn dump() {
+    if !INSTALLED.load(Ordering::Relaxed) { return; }
+    let messages = match lock().as_mut() { Some(ring) => ring.take(), None => return };
+    if messages.is_empty() { return; }
+
+    crate::output::write(Priority::DEBUG, format_args!("---- {} recent debug messages ----\r\n", messages.len()));
+    for message in messages.iter() {
+        let eol = if message.ends_with('\n') { "" } else { crate::output::EOL };
+        crate::output::write(Priority::DEBUG, format_args!("{}{}", message, eol));
+    }
+    crate::output::write(Priority::DEBUG, format_args!("---- end of recent debug messages ----\r\n"));
+}
+
+#[test]
+fn ring_eviction() {
+    let mut ring = Ring { config: FlightRecorder::new().max_messages(3).max_bytes(10), messages: VecDeque::new(), bytes: 0 };
+    for message in ["a\n", "b\n", "c\n", "d\n"] { ring.push(message.to_string()); }
+    assert_eq!(ring.messages, ["b\n", "c\n", "d\n"]);
+
+    ring.push("0123456\n".to_string());
+    assert_eq!((&ring.messages, ring.bytes), (&VecDeque::from(["d\n".to_string(), "0123456\n".to_string()]), 10));
+
+    assert_eq!(ring.take().len(), 2);
+    assert_eq!((ring.messages.len(), ring.bytes), (0, 0));
+}
@@ -0,0 +1,122 @@
+//! Integration with the [tracing](https://docs.rs/tracing/) crate.  Requires the `tracing` feature.
+//!
+//! This goes both ways:
+//!
+//! * Reports from [bug!], [unwrap!], [expect!], and [check!] are emitted as `ERROR` events with a target of
+//!   `"bugsalot"`, and structured `site`, `module`, `expression`, `expected`, `found`, `left`, and `right` fields.
+//! * [BreakOnErrorLayer] is a [Layer] that calls [debugger::break_if_attached] whenever an `ERROR` event is recorded.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! # use tracing0 as tracing;
+//! use tracing_subscriber::layer::SubscriberExt;
+//!
+//! let subscriber = tracing_subscriber::registry().with(bugsalot::tracing::BreakOnErrorLayer);
+//! tracing::subscriber::set_global_default(subscriber).unwrap();
+//!
+//! tracing::error!("The debugger will break here, if attached");
+//! ```
+//!
+//! [bug!]:                         ../macro.bug.html
+//! [unwrap!]:                      ../macro.unwrap.html
+//! [expect!]:                      ../macro.expect.html
+//! [check!]:                       ../macro.check.html
+//! [debugger::break_if_attached]:  ../debugger/fn.break_if_attached.html
+//! [Layer]:                        https://docs.rs/tracing-subscriber/0.3/tracing_subscriber/layer/trait.Layer.html
+
+use crate::macro_impl::Site;
+use tracing0 as tracing;
+use tracing::{Event, Level, Subscriber};
+use tracing_subscriber::layer::{Context, Layer};
+use std::fmt::{Debug, Display};
+
+/// The `target` of events emitted by bugsalot's macros.
+pub const TARGET : &str = "bugsalot";
+
+/// A [Layer] that calls [debugger::break_if_attached] when an `ERROR` event is recorded.
+///
+/// Events emitted by bugsalot's own macros are skipped, as the macros already break at their call site.
+///
+/// [debugger::break_if_attached]:  ../debugger/fn.break_if_attached.html
+/// [Layer]:                        https://docs.rs/tracing-subscriber/0.3/tracing_subscriber/layer/trait.Layer.html
+#[derive(Clone, Copy, Debug, Default)]
+pub struct BreakOnErrorLayer;
+
+impl<S: Subscriber> Layer<S> for BreakOnErrorLayer {
+    fn on_event(&self, event: &Event, _ctx: Context<S>) {
+        let meta = event.metadata();
+        if *meta.level() == Level::ERROR && meta.target() != TARGET {
+            crate::debugger::break_if_attached();
+        }
+    }
+}
+
+pub(crate) fn bug(site: &Site, msg: &dyn Display) {
+    tracing::error!(
+        target: TARGET,
+        site        = %site,
+        module      = site.module,
+        "{}", msg
+    );
+}
+
+pub(crate) fn unwrap_failed(site: &Site, msg: &dyn Display, expected: &dyn Display, found: &dyn Display) {
+    tracing::error!(
+        target: TARGET,
+        site        = %site,
+        module      = site.module,
+        expression  = site.expression,
+        expected    = %expected,
+        found       = %found,
+        "{}", msg
+    );
+}
+
+pub(crate) fn check_failed(site: &Site, msg: &dyn Display, operands: Option<(&dyn Debug, &dyn Debug)>) {
+    tracing::error!(
+        target: TARGET,
+        site        = %site,
+        module      = site.module,
+        expression  = site.expression,
+        left        = operands.map(|(left, _)| tracing::field::debug(left)),
+        right       = operands.map(|(_, right)| tracing::field::debug(right)),
+        "{}", msg
+    );
+}
+
+#[test]
+fn break_on_error_layer_examples() {
+    use std::{format, string::String, sync::{Arc, Mutex}, vec::Vec};
+    use tracing_subscriber::layer::SubscriberExt;
+
+    /// Records each event's level, target, and fields, as `level target name=value ...`
+    #[derive(Clone, Default)] struct Recorder(Arc<Mutex<Vec<String>>>);
+    struct Fields(String);
+    impl tracing::field::Visit for Fields {
+        fn record_str(&mut self, field: &tracing::field::Field, value: &str) { self.0 += &format!(" {}={}", field.name(), value); }
+        fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn Debug) { self.0 += &format!(" {}={:?}", field.name(), value); }
+    }
+    impl<S: Subscriber> Layer<S> for Recorder {
+        fn on_event(&self, event: &Event, _ctx: Context<S>) {
+            let mut fields = Fields(format!("{} {}", event.metadata().level(), event.metadata().target()));
+            event.record(&mut fields);
+            self.0.lock().unwrap().push(fields.0);
+        }
+    }
+
+    let recorder = Recorder::default();
+    let subscriber = tracing_subscriber::registry().with(BreakOnErrorLayer).with(recorder.clone());
+    tracing::subscriber::with_default(subscriber, || {
+        tracing::info!("break_on_error_layer_examples: info");
+        let _ = crate::unwrap!(Err::<i32, &str>("reason"), 0); // Skipped by BreakOnErrorLayer, thanks to its target
+    });
+
+    let events = recorder.0.lock().unwrap();
+    assert_eq!(events[0], format!("INFO {} message=break_on_error_layer_examples: info", module_path!()));
+    assert!(events[1].starts_with(&format!("ERROR {} message=unwrap! failed site=", TARGET)), "{}", events[1]);
+    assert!(events[1].ends_with(&format!(
+        " module={} expression=Err::<i32, &str>(\"reason\") expected=Ok found=Err(\"reason\")", module_path!()
+    )), "{}", events[1]);
+    assert_eq!(events.len(), 2);
+}